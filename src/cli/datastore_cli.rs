@@ -1,8 +1,38 @@
-use clap::{Args, Subcommand, ValueEnum};
+use std::{
+    fs::File,
+    fs::OpenOptions,
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use clap::{ArgGroup, Args, Subcommand, ValueEnum};
+use serde_json::Value;
 
+use super::confirm;
 use rbxcloud::rbx::{
+    error::Error,
+    expr::Expression,
+    json_schema::JsonSchema,
     types::{ReturnLimit, RobloxUserId, UniverseId},
     v1::{
+        datastore::EntryAttributes,
+        datastore_bulk_delete::{BulkDeleteKeys, DataStoreBulkDelete},
+        datastore_copy::{DataStoreCopyEntries, EntryTransform},
+        datastore_diff::{diff_entries, DataStoreDiffReport, DiffSource, KeyDiffStatus},
+        datastore_dump::{read_dump, DataStoreExportEntries, DataStoreImportEntries},
+        datastore_erasure::{
+            DataStoreErasure, DataStoreErasureReport, ErasureKeyTemplate, ErasureScanTarget,
+            ErasureStatus,
+        },
+        datastore_query::{query_entry, DataStoreQuery, QueryMatch},
+        datastore_restore::{
+            DataStoreRestoreEntries, DataStoreRestoreReport, RestoreAction, RestoreKeys,
+        },
+        datastore_snapshot::{read_snapshot_log, write_snapshot_record},
+        datastore_stats::{DataStoreStatsParams, DataStoreStatsReport},
+        datastore_watch::{DataStoreWatch, WatchKeys},
         DataStoreDeleteEntry, DataStoreGetEntry, DataStoreGetEntryVersion, DataStoreIncrementEntry,
         DataStoreListEntries, DataStoreListEntryVersions, DataStoreListStores, DataStoreSetEntry,
         RbxCloud,
@@ -91,6 +121,10 @@ pub enum DataStoreCommands {
         #[clap(short, long, value_parser)]
         key: String,
 
+        /// Output the value along with its version, user IDs, and attributes as JSON
+        #[clap(long, value_parser, default_value_t = false)]
+        metadata: bool,
+
         /// Universe ID of the experience
         #[clap(short, long, value_parser)]
         universe_id: u64,
@@ -134,6 +168,18 @@ pub enum DataStoreCommands {
         #[clap(short = 't', long, value_parser)]
         attributes: Option<String>,
 
+        /// Attribute as KEY=VALUE, where VALUE is parsed as JSON or else used as a string (can be multiple)
+        #[clap(long, value_parser)]
+        attribute: Vec<String>,
+
+        /// JSON Schema file the value must match
+        #[clap(long, value_parser)]
+        schema: Option<String>,
+
+        /// Do not check the request against DataStore limits before sending it
+        #[clap(long, value_parser, default_value_t = false)]
+        skip_preflight: bool,
+
         /// Universe ID of the experience
         #[clap(short, long, value_parser)]
         universe_id: u64,
@@ -173,6 +219,14 @@ pub enum DataStoreCommands {
         #[clap(short = 't', long, value_parser)]
         attributes: Option<String>,
 
+        /// Attribute as KEY=VALUE, where VALUE is parsed as JSON or else used as a string (can be multiple)
+        #[clap(long, value_parser)]
+        attribute: Vec<String>,
+
+        /// Do not check the request against DataStore limits before sending it
+        #[clap(long, value_parser, default_value_t = false)]
+        skip_preflight: bool,
+
         /// Universe ID of the experience
         #[clap(short, long, value_parser)]
         universe_id: u64,
@@ -278,257 +332,1226 @@ pub enum DataStoreCommands {
         #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
         api_key: String,
     },
-}
 
-#[derive(Debug, Clone, ValueEnum)]
-pub enum ListEntrySortOrder {
-    Ascending,
-    Descending,
-}
+    /// Copy (or migrate) entries to another DataStore, scope, or universe
+    #[clap(visible_alias = "migrate")]
+    Copy {
+        /// Source DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
 
-#[derive(Debug, Args)]
-pub struct DataStore {
-    #[clap(subcommand)]
-    command: DataStoreCommands,
-}
+        /// Source DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
 
-#[inline]
-fn u64_ids_to_roblox_ids(user_ids: Option<Vec<u64>>) -> Option<Vec<RobloxUserId>> {
-    user_ids.map(|ids| {
-        ids.into_iter()
-            .map(RobloxUserId)
-            .collect::<Vec<RobloxUserId>>()
-    })
-}
+        /// If true, copy keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
 
-impl DataStore {
-    pub async fn run(self) -> anyhow::Result<Option<String>> {
-        match self.command {
-            DataStoreCommands::ListStores {
-                prefix,
-                limit,
-                cursor,
-                universe_id,
-                pretty,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let res = datastore
-                    .list_stores(&DataStoreListStores {
-                        cursor,
-                        limit: ReturnLimit(limit),
-                        prefix,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => {
-                        let r = if pretty {
-                            serde_json::to_string_pretty(&data)?
-                        } else {
-                            serde_json::to_string(&data)?
-                        };
-                        Ok(Some(r))
-                    }
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// Only copy keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
 
-            DataStoreCommands::List {
-                prefix,
-                limit,
-                cursor,
-                universe_id,
-                api_key,
-                datastore_name,
-                scope,
-                pretty,
-                all_scopes,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let res = datastore
-                    .list_entries(&DataStoreListEntries {
-                        name: datastore_name,
-                        scope,
-                        all_scopes,
-                        prefix,
-                        limit: ReturnLimit(limit),
-                        cursor,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => {
-                        let r = if pretty {
-                            serde_json::to_string_pretty(&data)?
-                        } else {
-                            serde_json::to_string(&data)?
-                        };
-                        Ok(Some(r))
-                    }
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// Source universe ID
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
 
-            DataStoreCommands::Get {
-                datastore_name,
-                scope,
-                key,
-                universe_id,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let res = datastore
-                    .get_entry_string(&DataStoreGetEntry {
-                        name: datastore_name,
-                        scope,
-                        key,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => Ok(Some(data)),
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// Destination DataStore name (defaults to the source name)
+        #[clap(long, value_parser)]
+        dest_datastore_name: Option<String>,
 
-            DataStoreCommands::Set {
-                datastore_name,
-                scope,
-                key,
-                match_version,
-                exclusive_create,
-                data,
-                user_ids,
-                attributes,
-                universe_id,
-                pretty,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let ids = u64_ids_to_roblox_ids(user_ids);
-                let res = datastore
-                    .set_entry(&DataStoreSetEntry {
-                        name: datastore_name,
-                        scope,
-                        key,
-                        match_version,
-                        exclusive_create,
-                        roblox_entry_user_ids: ids,
-                        roblox_entry_attributes: attributes,
-                        data,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => {
-                        let r = if pretty {
-                            serde_json::to_string_pretty(&data)?
-                        } else {
-                            serde_json::to_string(&data)?
-                        };
-                        Ok(Some(r))
-                    }
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// Destination scope (defaults to each entry's source scope)
+        #[clap(long, value_parser)]
+        dest_scope: Option<String>,
 
-            DataStoreCommands::Increment {
-                datastore_name,
-                scope,
-                key,
-                increment_by,
-                user_ids,
-                attributes,
-                universe_id,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let ids = u64_ids_to_roblox_ids(user_ids);
-                let res = datastore
-                    .increment_entry(&DataStoreIncrementEntry {
-                        name: datastore_name,
-                        scope,
-                        key,
-                        roblox_entry_user_ids: ids,
-                        roblox_entry_attributes: attributes,
-                        increment_by,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => Ok(Some(format!("{data}"))),
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// Destination universe ID (defaults to the source universe)
+        #[clap(long, value_parser)]
+        dest_universe_id: Option<u64>,
 
-            DataStoreCommands::Delete {
-                datastore_name,
-                scope,
-                key,
-                universe_id,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let res = datastore
-                    .delete_entry(&DataStoreDeleteEntry {
-                        name: datastore_name,
-                        scope,
-                        key,
-                    })
-                    .await;
-                match res {
-                    Ok(_) => Ok(None),
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// API key for the destination universe (defaults to the source API key)
+        #[clap(long, value_parser, env = "RBXCLOUD_DEST_API_KEY")]
+        dest_api_key: Option<String>,
 
-            DataStoreCommands::ListVersions {
-                datastore_name,
-                scope,
-                key,
-                start_time,
-                end_time,
-                sort_order,
-                limit,
-                cursor,
-                universe_id,
-                pretty,
-                api_key,
-            } => {
-                let rbx_cloud = RbxCloud::new(&api_key);
-                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
-                let res = datastore
-                    .list_entry_versions(&DataStoreListEntryVersions {
-                        name: datastore_name,
-                        scope,
-                        key,
-                        start_time,
-                        end_time,
-                        sort_order: format!("{sort_order:?}"),
-                        limit: ReturnLimit(limit),
-                        cursor,
-                    })
-                    .await;
-                match res {
-                    Ok(data) => {
-                        let r = if pretty {
-                            serde_json::to_string_pretty(&data)?
-                        } else {
-                            serde_json::to_string(&data)?
-                        };
-                        Ok(Some(r))
-                    }
-                    Err(err) => Err(err.into()),
-                }
-            }
+        /// jq-style expression applied to each value (a `null` result skips the entry)
+        #[clap(short, long, value_parser)]
+        transform: Option<String>,
 
-            DataStoreCommands::GetVersion {
-                datastore_name,
-                scope,
-                key,
-                version_id,
+        /// Do not overwrite entries that already exist in the destination
+        #[clap(long, value_parser, default_value_t = false)]
+        skip_existing: bool,
+
+        /// Read back each written entry and compare MD5 checksums
+        #[clap(long, value_parser, default_value_t = false)]
+        verify: bool,
+
+        /// Delete each source entry after it has been copied (and verified)
+        #[clap(long, value_parser, default_value_t = false)]
+        delete_source: bool,
+
+        /// Report what would be copied without writing anything
+        #[clap(long, value_parser, default_value_t = false)]
+        dry_run: bool,
+
+        /// Maximum number of entries copied at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Take a snapshot of all DataStores in the experience
+    Snapshot {
+        /// Append the snapshot to this snapshot log (NDJSON)
+        #[clap(long, value_parser)]
+        log: Option<String>,
+
+        /// Note recorded with the snapshot, e.g. the deploy it was taken for
+        #[clap(short, long, value_parser)]
+        label: Option<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Restore entries to the latest version at or before a point in time
+    #[clap(group(ArgGroup::new("target").required(true).args(["key", "prefix", "all"])))]
+    #[clap(group(ArgGroup::new("point").required(true).args(["at", "before_version"])))]
+    Restore {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, restore keys from all scopes (with --prefix or --all)
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Restore a single key
+        #[clap(short, long, value_parser)]
+        key: Option<String>,
+
+        /// Restore every existing key with this prefix (deleted keys are not listed; restore them with --key)
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// Restore every existing key in the DataStore (deleted keys are not listed; restore them with --key)
+        #[clap(long, value_parser)]
+        all: bool,
+
+        /// Point in time to restore to (ISO UTC Datetime, e.g. 2026-10-01T12:00Z)
+        #[clap(short = 't', long, value_parser = parse_timestamp)]
+        at: Option<DateTime<Utc>>,
+
+        /// Restore to the snapshot taken before this version of --key was written
+        #[clap(long, value_parser, requires = "snapshot_log")]
+        before_version: Option<String>,
+
+        /// Snapshot log written by `datastore snapshot --log`
+        #[clap(long, value_parser)]
+        snapshot_log: Option<String>,
+
+        /// Write a JSON report of every key and its old and new value to this file
+        #[clap(long, value_parser)]
+        report: Option<String>,
+
+        /// Only preview the restore; do not write anything
+        #[clap(long, value_parser, default_value_t = false)]
+        dry_run: bool,
+
+        /// Skip the confirmation prompt
+        #[clap(short, long, value_parser, default_value_t = false)]
+        yes: bool,
+
+        /// Maximum number of keys processed at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Watch keys and print every new version as it is written
+    #[clap(group(ArgGroup::new("target").required(true).args(["key", "prefix"])))]
+    Watch {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Watch this key (can be multiple)
+        #[clap(short, long, value_parser)]
+        key: Vec<String>,
+
+        /// Watch every key with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// Seconds between two polls of the same key
+        #[clap(short, long, value_parser, default_value_t = 5)]
+        interval: u64,
+
+        /// Maximum number of requests sent per minute (0 for no limit)
+        #[clap(long, value_parser, default_value_t = 120)]
+        max_requests_per_minute: u32,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print each event
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Report key counts, value sizes, and the largest keys of DataStores
+    Stats {
+        /// DataStore name (can be multiple; defaults to every DataStore)
+        #[clap(short, long, value_parser)]
+        datastore_name: Vec<String>,
+
+        /// Only report on DataStores with this name prefix
+        #[clap(long, value_parser)]
+        store_prefix: Option<String>,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, count keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Only count keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// Number of largest keys to report per DataStore
+        #[clap(short, long, value_parser, default_value_t = 10)]
+        top: usize,
+
+        /// Flag keys using at least this fraction of the 4MB value limit
+        #[clap(long, value_parser, default_value_t = 0.5)]
+        flag_threshold: f64,
+
+        /// Also count the versions of every key (one extra request per 100 versions)
+        #[clap(long, value_parser, default_value_t = false)]
+        versions: bool,
+
+        /// Maximum number of keys processed at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Output format
+        #[clap(long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Export entries as newline-delimited JSON
+    Export {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, export keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Only export keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// File to write to (defaults to stdout)
+        #[clap(short = 'f', long, value_parser)]
+        output: Option<String>,
+
+        /// Add to the output file if it already exists, instead of refusing to run
+        #[clap(long, value_parser, default_value_t = false, requires = "output")]
+        append: bool,
+
+        /// Maximum number of entries fetched at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Compare two DataStores, scopes, universes, or NDJSON exports
+    Diff {
+        /// DataStore name of the old side
+        #[clap(short, long, value_parser)]
+        datastore_name: Option<String>,
+
+        /// DataStore scope of the old side
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, read keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Only compare keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// Universe ID of the old side
+        #[clap(short, long, value_parser)]
+        universe_id: Option<u64>,
+
+        /// NDJSON export to use as the old side instead of a live DataStore
+        #[clap(short = 'f', long, value_parser)]
+        file: Option<String>,
+
+        /// DataStore name of the new side (defaults to the old side's)
+        #[clap(long, value_parser)]
+        other_datastore_name: Option<String>,
+
+        /// DataStore scope of the new side (defaults to the old side's)
+        #[clap(long, value_parser)]
+        other_scope: Option<String>,
+
+        /// Universe ID of the new side (defaults to the old side's)
+        #[clap(long, value_parser)]
+        other_universe_id: Option<u64>,
+
+        /// NDJSON export to use as the new side instead of a live DataStore
+        #[clap(long, value_parser)]
+        other_file: Option<String>,
+
+        /// API key for the new side (defaults to --api-key)
+        #[clap(long, value_parser, env = "RBXCLOUD_OTHER_API_KEY")]
+        other_api_key: Option<String>,
+
+        /// Match entries by scope and key rather than by key alone
+        #[clap(long, value_parser, default_value_t = false)]
+        compare_scopes: bool,
+
+        /// Output format
+        #[clap(long, value_enum, default_value_t = DiffFormat::Human)]
+        format: DiffFormat,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Maximum number of entries fetched at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: Option<String>,
+    },
+
+    /// Search entries whose value matches a filter expression
+    Query {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: Option<String>,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, search keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Only search keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// jq-style predicate on the value, e.g. '.coins > 1e9 and (.banned | not)'
+        #[clap(long, value_parser)]
+        filter: Option<String>,
+
+        /// jq-style expression selecting what to output for each match, e.g. '{coins, level: .stats.level}'
+        #[clap(long, value_parser)]
+        select: Option<String>,
+
+        /// Search an NDJSON export instead of a live DataStore
+        #[clap(short = 'f', long, value_parser)]
+        file: Option<String>,
+
+        /// Stop after this many matches
+        #[clap(short, long, value_parser)]
+        limit: Option<u64>,
+
+        /// Stop after reading this many values
+        #[clap(long, value_parser)]
+        max_reads: Option<u64>,
+
+        /// Maximum number of values fetched at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Pretty-print each match
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: Option<u64>,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: Option<String>,
+    },
+
+    /// Import entries from newline-delimited JSON (an export or a bulk-delete backup)
+    Import {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// Write every entry into this scope (defaults to each entry's own scope)
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// NDJSON file to import
+        #[clap(short = 'f', long, value_parser)]
+        file: String,
+
+        /// Do not overwrite entries that already exist
+        #[clap(long, value_parser, default_value_t = false)]
+        skip_existing: bool,
+
+        /// JSON Schema file every value must match
+        #[clap(long, value_parser)]
+        schema: Option<String>,
+
+        /// Do not check the request against DataStore limits before sending it
+        #[clap(long, value_parser, default_value_t = false)]
+        skip_preflight: bool,
+
+        /// Maximum number of entries written at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Delete many entries at once, backing up each value to a local file first
+    #[clap(group(ArgGroup::new("target").required(true).args(["prefix", "keys_file", "all"])))]
+    BulkDelete {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, delete keys from all scopes (with --prefix or --all)
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Delete every key with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// Delete the keys listed in this file (one per line)
+        #[clap(short, long, value_parser)]
+        keys_file: Option<String>,
+
+        /// Delete every key in the scope
+        #[clap(long, value_parser)]
+        all: bool,
+
+        /// Backup file for the deleted values (defaults to <DATASTORE_NAME>-backup-<TIME>.ndjson)
+        #[clap(short, long, value_parser)]
+        backup: Option<String>,
+
+        /// Add to the backup file if it already exists, instead of refusing to run
+        #[clap(long, value_parser, default_value_t = false)]
+        append: bool,
+
+        /// Skip the confirmation prompt
+        #[clap(short, long, value_parser, default_value_t = false)]
+        yes: bool,
+
+        /// Maximum number of entries deleted at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Delete every entry associated with a user (right-to-erasure requests)
+    #[clap(group(ArgGroup::new("source").required(true).multiple(true).args(["key_template", "ordered_key_template", "scan", "scan_ordered_datastore"])))]
+    EraseUser {
+        /// Roblox user ID to erase
+        #[clap(long, value_parser)]
+        user_id: u64,
+
+        /// DataStore key template as DATASTORE:TEMPLATE, with {id} replaced by the user ID (can be multiple)
+        #[clap(short, long, value_parser)]
+        key_template: Vec<String>,
+
+        /// OrderedDataStore entry template as DATASTORE:TEMPLATE (can be multiple)
+        #[clap(long, value_parser)]
+        ordered_key_template: Vec<String>,
+
+        /// Scope of the templated keys and scanned OrderedDataStores
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Scan DataStore entries for the user ID in their metadata
+        #[clap(long, value_parser)]
+        scan: bool,
+
+        /// Only scan these DataStores (defaults to every DataStore; can be multiple)
+        #[clap(long, value_parser, requires = "scan")]
+        scan_datastore: Vec<String>,
+
+        /// Scan an OrderedDataStore for entries whose ID is the user ID (can be multiple)
+        #[clap(long, value_parser)]
+        scan_ordered_datastore: Vec<String>,
+
+        /// Write the audit report to this file
+        #[clap(long, value_parser)]
+        report: Option<String>,
+
+        /// Only list the matching entries
+        #[clap(long, value_parser, default_value_t = false)]
+        dry_run: bool,
+
+        /// Skip the confirmation prompt
+        #[clap(short, long, value_parser, default_value_t = false)]
+        yes: bool,
+
+        /// Maximum number of entries fetched or deleted at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Validate existing entries against a JSON Schema
+    Validate {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// If true, validate keys from all scopes
+        #[clap(short = 'o', long, value_parser)]
+        all_scopes: bool,
+
+        /// Only validate keys with this prefix
+        #[clap(short = 'r', long, value_parser)]
+        prefix: Option<String>,
+
+        /// JSON Schema file
+        #[clap(long, value_parser)]
+        schema: String,
+
+        /// Only apply the schema to keys matching this pattern (`*` and `?` wildcards)
+        #[clap(short, long, value_parser, default_value = "*")]
+        key_pattern: String,
+
+        /// Maximum number of entries fetched at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Inspect a ProfileService/ProfileStore profile
+    ProfileGet {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The key of the profile
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Only output the profile's Data
+        #[clap(long, value_parser, default_value_t = false)]
+        data_only: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Edit the Data of a ProfileService/ProfileStore profile
+    #[clap(group(ArgGroup::new("edit").required(true).args(["data", "transform"])))]
+    ProfileEdit {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The key of the profile
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified data replacing the profile's Data
+        #[clap(short = 'D', long, value_parser)]
+        data: Option<String>,
+
+        /// Expression applied to the profile's Data, e.g. `.Banned = false`
+        #[clap(long, value_parser)]
+        transform: Option<String>,
+
+        /// Edit the profile even if a game server holds its session lock and saved it in the last 30 minutes
+        #[clap(long, value_parser, default_value_t = false)]
+        force: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Push a global update to a ProfileService profile
+    ProfilePushUpdate {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The key of the profile
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified update data
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum DiffFormat {
+    Human,
+    Json,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ListEntrySortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Args)]
+pub struct DataStore {
+    #[clap(subcommand)]
+    command: DataStoreCommands,
+}
+
+#[inline]
+pub(crate) fn parse_attributes(
+    json: Option<String>,
+    pairs: Vec<String>,
+) -> anyhow::Result<Option<EntryAttributes>> {
+    let mut attributes = match json {
+        Some(json) => match serde_json::from_str::<Value>(&json)? {
+            Value::Object(map) => map,
+            _ => anyhow::bail!("attributes must be a JSON object"),
+        },
+        None if pairs.is_empty() => return Ok(None),
+        None => EntryAttributes::new(),
+    };
+    for pair in pairs {
+        let Some((key, value)) = pair.split_once('=') else {
+            anyhow::bail!("invalid attribute '{pair}' (expected KEY=VALUE)");
+        };
+        let value = serde_json::from_str::<Value>(value)
+            .unwrap_or_else(|_| Value::String(value.to_string()));
+        attributes.insert(key.to_string(), value);
+    }
+    Ok(Some(attributes))
+}
+
+fn u64_ids_to_roblox_ids(user_ids: Option<Vec<u64>>) -> Option<Vec<RobloxUserId>> {
+    user_ids.map(|ids| {
+        ids.into_iter()
+            .map(RobloxUserId)
+            .collect::<Vec<RobloxUserId>>()
+    })
+}
+
+fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(s.trim_end_matches('Z'), "%Y-%m-%dT%H:%M")
+        .map(|time| time.and_utc())
+        .map_err(|_| format!("invalid timestamp '{s}' (expected e.g. 2026-10-01T12:00:00Z)"))
+}
+
+fn summarize_restore(report: &DataStoreRestoreReport) -> String {
+    let mut summary = format!(
+        "restore: {}, delete: {}, unchanged: {}, no version: {}, failed: {}",
+        report.count(RestoreAction::Restore),
+        report.count(RestoreAction::Delete),
+        report.count(RestoreAction::Unchanged),
+        report.count(RestoreAction::NoVersion),
+        report.count(RestoreAction::Failed),
+    );
+    for entry in report.changes() {
+        summary.push_str(&format!(
+            "\n  {:?} {}/{}: {} -> {}",
+            entry.action,
+            entry.scope,
+            entry.key,
+            entry.current_version.as_deref().unwrap_or("(none)"),
+            entry.target_version.as_deref().unwrap_or("(none)"),
+        ));
+    }
+    summary
+}
+
+fn parse_key_templates(
+    templates: Vec<String>,
+    scope: &Option<String>,
+) -> anyhow::Result<Vec<ErasureKeyTemplate>> {
+    templates
+        .into_iter()
+        .map(|t| match t.split_once(':') {
+            Some((name, template)) if !name.is_empty() && !template.is_empty() => {
+                Ok(ErasureKeyTemplate {
+                    name: name.to_string(),
+                    scope: scope.clone(),
+                    template: template.to_string(),
+                })
+            }
+            _ => anyhow::bail!("invalid key template '{t}' (expected DATASTORE:TEMPLATE)"),
+        })
+        .collect()
+}
+
+fn summarize_erasure(report: &DataStoreErasureReport) -> String {
+    let mut summary = format!(
+        "checked: {}, found: {}, deleted: {}, missing: {}, failed: {}, lookup errors: {}",
+        report.checked,
+        report.entries.len(),
+        report.count(ErasureStatus::Deleted),
+        report.count(ErasureStatus::Missing),
+        report.count(ErasureStatus::Failed),
+        report.errors.len(),
+    );
+    for entry in &report.entries {
+        summary.push_str(&format!(
+            "\n  {:?} {}/{}/{} ({:?})",
+            entry.source, entry.name, entry.scope, entry.key, entry.matched_by
+        ));
+    }
+    for error in &report.errors {
+        summary.push_str(&format!("\n  error: {error}"));
+    }
+    summary
+}
+
+/// Open a file to write to without truncating it. An existing file is
+/// refused unless `append` is set, in which case writes go to its end.
+fn open_output(path: &str, append: bool) -> anyhow::Result<File> {
+    let mut options = OpenOptions::new();
+    if append {
+        options.create(true).append(true);
+    } else {
+        options.write(true).create_new(true);
+    }
+    options.open(path).map_err(|err| match err.kind() {
+        ErrorKind::AlreadyExists => {
+            anyhow::anyhow!("{path} already exists; pass --append to add to it")
+        }
+        _ => err.into(),
+    })
+}
+
+fn format_diff(report: &DataStoreDiffReport) -> String {
+    let mut out = String::new();
+    for key in &report.keys {
+        match key.status {
+            KeyDiffStatus::Added => out.push_str(&format!("+ {}\n", key.key)),
+            KeyDiffStatus::Removed => out.push_str(&format!("- {}\n", key.key)),
+            KeyDiffStatus::Changed => {
+                out.push_str(&format!("~ {}\n", key.key));
+                for change in &key.changes {
+                    out.push_str(&format!("    {change}\n"));
+                }
+            }
+        }
+    }
+    for err in &report.errors {
+        out.push_str(&format!("! {}/{}: {}\n", err.scope, err.key, err.error));
+    }
+    out.push_str(&format!(
+        "{} added, {} removed, {} changed, {} unchanged",
+        report.added, report.removed, report.changed, report.unchanged
+    ));
+    if !report.errors.is_empty() {
+        out.push_str(&format!(", {} unreadable", report.errors.len()));
+    }
+    out
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        s if s >= 1024 * 1024 => format!("{:.1}MB", s as f64 / (1024.0 * 1024.0)),
+        s if s >= 1024 => format!("{:.1}KB", s as f64 / 1024.0),
+        s => format!("{s}B"),
+    }
+}
+
+fn format_stats(report: &DataStoreStatsReport) -> String {
+    let mut out = String::new();
+    for ds in &report.datastores {
+        out.push_str(&format!(
+            "{}: {} keys, {} total, {} max",
+            ds.name,
+            ds.key_count,
+            format_size(ds.total_size),
+            format_size(ds.max_size)
+        ));
+        if let Some(versions) = ds.version_count {
+            out.push_str(&format!(", {versions} versions"));
+        }
+        out.push('\n');
+        for (scope, count) in &ds.scopes {
+            out.push_str(&format!("  scope {scope:<20} {count:>10} keys\n"));
+        }
+        for bucket in &ds.size_distribution {
+            out.push_str(&format!(
+                "  size  {:<20} {:>10}\n",
+                bucket.range, bucket.count
+            ));
+        }
+        for key in &ds.largest_keys {
+            let flag = if ds
+                .flagged_keys
+                .iter()
+                .any(|f| f.scope == key.scope && f.key == key.key)
+            {
+                " !"
+            } else {
+                ""
+            };
+            let versions = key
+                .versions
+                .map(|v| format!(" {v} versions"))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "  {:>10} {:>5.1}%{versions} {}/{}{flag}\n",
+                format_size(key.size),
+                key.limit_usage * 100.0,
+                key.scope,
+                key.key
+            ));
+        }
+        if !ds.flagged_keys.is_empty() {
+            out.push_str(&format!(
+                "  {} keys flagged (!) as close to the 4MB limit\n",
+                ds.flagged_keys.len()
+            ));
+        }
+        for err in &ds.errors {
+            out.push_str(&format!("  error: {err}\n"));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn expression_transform(source: Option<String>) -> anyhow::Result<Option<EntryTransform>> {
+    let Some(source) = source else {
+        return Ok(None);
+    };
+    let expr = Expression::parse(&source)?;
+    let transform: EntryTransform = Arc::new(move |_key, value| {
+        let value = expr.evaluate(&value)?;
+        Ok((!value.is_null()).then_some(value))
+    });
+    Ok(Some(transform))
+}
+
+impl DataStore {
+    pub async fn run(self) -> anyhow::Result<Option<String>> {
+        match self.command {
+            DataStoreCommands::ListStores {
+                prefix,
+                limit,
+                cursor,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let res = datastore
+                    .list_stores(&DataStoreListStores {
+                        cursor,
+                        limit: ReturnLimit(limit),
+                        prefix,
+                    })
+                    .await;
+                match res {
+                    Ok(data) => {
+                        let r = if pretty {
+                            serde_json::to_string_pretty(&data)?
+                        } else {
+                            serde_json::to_string(&data)?
+                        };
+                        Ok(Some(r))
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::List {
+                prefix,
+                limit,
+                cursor,
+                universe_id,
+                api_key,
+                datastore_name,
+                scope,
+                pretty,
+                all_scopes,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let res = datastore
+                    .list_entries(&DataStoreListEntries {
+                        name: datastore_name,
+                        scope,
+                        all_scopes,
+                        prefix,
+                        limit: ReturnLimit(limit),
+                        cursor,
+                    })
+                    .await;
+                match res {
+                    Ok(data) => {
+                        let r = if pretty {
+                            serde_json::to_string_pretty(&data)?
+                        } else {
+                            serde_json::to_string(&data)?
+                        };
+                        Ok(Some(r))
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::Get {
+                datastore_name,
+                scope,
+                key,
+                metadata,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let params = DataStoreGetEntry {
+                    name: datastore_name,
+                    scope,
+                    key,
+                };
+                if metadata {
+                    let entry = datastore.get_entry_with_metadata(&params).await?;
+                    return Ok(Some(serde_json::to_string(&entry)?));
+                }
+                let res = datastore.get_entry_string(&params).await;
+                match res {
+                    Ok(data) => Ok(Some(data)),
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::Set {
+                datastore_name,
+                scope,
+                key,
+                match_version,
+                exclusive_create,
+                data,
+                user_ids,
+                attributes,
+                attribute,
+                schema,
+                skip_preflight,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let mut datastore = rbx_cloud
+                    .datastore(UniverseId(universe_id))
+                    .with_preflight(!skip_preflight);
+                if let Some(schema) = schema {
+                    datastore.register_schema(
+                        &datastore_name,
+                        "*",
+                        JsonSchema::from_file(&schema)?,
+                    );
+                }
+                let ids = u64_ids_to_roblox_ids(user_ids);
+                let res = datastore
+                    .set_entry(&DataStoreSetEntry {
+                        name: datastore_name,
+                        scope,
+                        key,
+                        match_version,
+                        exclusive_create,
+                        roblox_entry_user_ids: ids,
+                        roblox_entry_attributes: parse_attributes(attributes, attribute)?,
+                        data,
+                    })
+                    .await;
+                match res {
+                    Ok(data) => {
+                        let r = if pretty {
+                            serde_json::to_string_pretty(&data)?
+                        } else {
+                            serde_json::to_string(&data)?
+                        };
+                        Ok(Some(r))
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::Increment {
+                datastore_name,
+                scope,
+                key,
+                increment_by,
+                user_ids,
+                attributes,
+                attribute,
+                skip_preflight,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud
+                    .datastore(UniverseId(universe_id))
+                    .with_preflight(!skip_preflight);
+                let ids = u64_ids_to_roblox_ids(user_ids);
+                let res = datastore
+                    .increment_entry(&DataStoreIncrementEntry {
+                        name: datastore_name,
+                        scope,
+                        key,
+                        roblox_entry_user_ids: ids,
+                        roblox_entry_attributes: parse_attributes(attributes, attribute)?,
+                        increment_by,
+                    })
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(format!("{data}"))),
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::Delete {
+                datastore_name,
+                scope,
+                key,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let res = datastore
+                    .delete_entry(&DataStoreDeleteEntry {
+                        name: datastore_name,
+                        scope,
+                        key,
+                    })
+                    .await;
+                match res {
+                    Ok(_) => Ok(None),
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::ListVersions {
+                datastore_name,
+                scope,
+                key,
+                start_time,
+                end_time,
+                sort_order,
+                limit,
+                cursor,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let res = datastore
+                    .list_entry_versions(&DataStoreListEntryVersions {
+                        name: datastore_name,
+                        scope,
+                        key,
+                        start_time,
+                        end_time,
+                        sort_order: format!("{sort_order:?}"),
+                        limit: ReturnLimit(limit),
+                        cursor,
+                    })
+                    .await;
+                match res {
+                    Ok(data) => {
+                        let r = if pretty {
+                            serde_json::to_string_pretty(&data)?
+                        } else {
+                            serde_json::to_string(&data)?
+                        };
+                        Ok(Some(r))
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+
+            DataStoreCommands::GetVersion {
+                datastore_name,
+                scope,
+                key,
+                version_id,
                 universe_id,
                 api_key,
             } => {
@@ -547,6 +1570,802 @@ impl DataStore {
                     Err(err) => Err(err.into()),
                 }
             }
+
+            DataStoreCommands::Snapshot {
+                log,
+                label,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let record = datastore.take_snapshot(label).await?;
+                if let Some(path) = log {
+                    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+                    write_snapshot_record(&mut file, &record)?;
+                }
+                let r = if pretty {
+                    serde_json::to_string_pretty(&record)?
+                } else {
+                    serde_json::to_string(&record)?
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::Restore {
+                datastore_name,
+                scope,
+                all_scopes,
+                key,
+                prefix,
+                all: _,
+                at,
+                before_version,
+                snapshot_log,
+                report,
+                dry_run,
+                yes,
+                concurrency,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let at = match (at, before_version, snapshot_log) {
+                    (Some(at), _, _) => at,
+                    (None, Some(version_id), Some(log)) => {
+                        let Some(version_key) = key.clone() else {
+                            anyhow::bail!("--before-version requires --key");
+                        };
+                        let records = read_snapshot_log(BufReader::new(File::open(&log)?))?;
+                        let snapshot = datastore
+                            .snapshot_before_version(
+                                &records,
+                                &DataStoreGetEntryVersion {
+                                    name: datastore_name.clone(),
+                                    scope: scope.clone(),
+                                    key: version_key,
+                                    version_id,
+                                },
+                            )
+                            .await?;
+                        let Some(snapshot_time) = snapshot.snapshot_time else {
+                            anyhow::bail!("the snapshot log has no time for the snapshot");
+                        };
+                        eprintln!(
+                            "Using snapshot {}{}",
+                            snapshot_time.to_rfc3339(),
+                            snapshot
+                                .label
+                                .as_ref()
+                                .map(|l| format!(" ({l})"))
+                                .unwrap_or_default()
+                        );
+                        snapshot_time
+                    }
+                    _ => anyhow::bail!("either --at or --before-version is required"),
+                };
+                let keys = match (key, prefix) {
+                    (Some(key), _) => RestoreKeys::Key(key),
+                    (None, Some(prefix)) => RestoreKeys::Prefix(prefix),
+                    (None, None) => RestoreKeys::All,
+                };
+                let plan = datastore
+                    .plan_restore(&DataStoreRestoreEntries {
+                        name: datastore_name,
+                        scope,
+                        all_scopes,
+                        keys,
+                        at,
+                        concurrency,
+                    })
+                    .await?;
+                eprintln!("Restoring {} to {}", plan.name, plan.at);
+                eprintln!("{}", summarize_restore(&plan));
+
+                let has_changes = plan.changes().next().is_some();
+                let result = if dry_run || !has_changes {
+                    plan
+                } else if yes || confirm("Apply restore?")? {
+                    datastore.apply_restore(&plan, concurrency).await?
+                } else {
+                    return Ok(Some("restore cancelled".to_string()));
+                };
+
+                if let Some(path) = report {
+                    std::fs::write(&path, serde_json::to_string_pretty(&result)?)?;
+                }
+                let applied = result.entries.iter().filter(|e| e.applied).count();
+                let failed = result.count(RestoreAction::Failed);
+                let summary = format!("{}\napplied: {applied}", summarize_restore(&result));
+                if failed > 0 {
+                    println!("{summary}");
+                    anyhow::bail!("{failed} entries failed to restore");
+                }
+                Ok(Some(summary))
+            }
+
+            DataStoreCommands::Watch {
+                datastore_name,
+                scope,
+                key,
+                prefix,
+                interval,
+                max_requests_per_minute,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let keys = match prefix {
+                    Some(prefix) => WatchKeys::Prefix(prefix),
+                    None => WatchKeys::Keys(key),
+                };
+                let mut events = datastore.watch(DataStoreWatch {
+                    name: datastore_name,
+                    scope,
+                    keys,
+                    poll_interval: std::time::Duration::from_secs(interval),
+                    max_requests_per_minute,
+                });
+                while let Some(event) = events.recv().await {
+                    match event {
+                        Ok(event) => {
+                            let r = if pretty {
+                                serde_json::to_string_pretty(&event)?
+                            } else {
+                                serde_json::to_string(&event)?
+                            };
+                            println!("{r}");
+                        }
+                        Err(err) => eprintln!("{err}"),
+                    }
+                }
+                Ok(None)
+            }
+
+            DataStoreCommands::Query {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                filter,
+                select,
+                file,
+                limit,
+                max_reads,
+                concurrency,
+                pretty,
+                universe_id,
+                api_key,
+            } => {
+                let filter = filter.as_deref().map(Expression::parse).transpose()?;
+                let select = select.as_deref().map(Expression::parse).transpose()?;
+                let print = |found: &QueryMatch| -> anyhow::Result<()> {
+                    let r = if pretty {
+                        serde_json::to_string_pretty(found)?
+                    } else {
+                        serde_json::to_string(found)?
+                    };
+                    println!("{r}");
+                    Ok(())
+                };
+                let mut count = 0;
+                if let Some(path) = file {
+                    let entries = read_dump(BufReader::new(File::open(path)?))?;
+                    for entry in entries
+                        .into_iter()
+                        .filter(|e| prefix.as_ref().is_none_or(|p| e.key.starts_with(p)))
+                        .filter(|e| all_scopes || scope.as_ref().is_none_or(|s| &e.scope == s))
+                        .take(max_reads.map_or(usize::MAX, |n| n as usize))
+                    {
+                        if limit.is_some_and(|limit| count >= limit) {
+                            break;
+                        }
+                        if let Some(found) = query_entry(entry, filter.as_ref(), select.as_ref())? {
+                            print(&found)?;
+                            count += 1;
+                        }
+                    }
+                } else {
+                    let (Some(name), Some(universe_id), Some(api_key)) =
+                        (datastore_name, universe_id, api_key)
+                    else {
+                        anyhow::bail!(
+                            "a live query needs --datastore-name, --universe-id and --api-key"
+                        );
+                    };
+                    let rbx_cloud = RbxCloud::new(&api_key);
+                    let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                    let mut matches = datastore.query(DataStoreQuery {
+                        name,
+                        scope,
+                        all_scopes,
+                        prefix,
+                        filter,
+                        select,
+                        concurrency,
+                        max_reads,
+                        limit,
+                    });
+                    while let Some(found) = matches.recv().await {
+                        match found {
+                            Ok(found) => {
+                                print(&found)?;
+                                count += 1;
+                            }
+                            Err(err) => eprintln!("{err}"),
+                        }
+                    }
+                }
+                eprintln!("{count} matches");
+                Ok(None)
+            }
+
+            DataStoreCommands::Stats {
+                datastore_name,
+                store_prefix,
+                scope,
+                all_scopes,
+                prefix,
+                top,
+                flag_threshold,
+                versions,
+                concurrency,
+                format,
+                pretty,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let report = datastore
+                    .stats(&DataStoreStatsParams {
+                        names: datastore_name,
+                        name_prefix: store_prefix,
+                        scope,
+                        all_scopes,
+                        prefix,
+                        top,
+                        flag_threshold,
+                        count_versions: versions,
+                        concurrency,
+                    })
+                    .await?;
+                let r = match format {
+                    StatsFormat::Table => format_stats(&report),
+                    StatsFormat::Json if pretty => serde_json::to_string_pretty(&report)?,
+                    StatsFormat::Json => serde_json::to_string(&report)?,
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::Export {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                output,
+                append,
+                concurrency,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let params = DataStoreExportEntries {
+                    name: datastore_name,
+                    scope,
+                    all_scopes,
+                    prefix,
+                    concurrency,
+                };
+                let (report, destination) = match &output {
+                    Some(path) => {
+                        let mut writer = BufWriter::new(open_output(path, append)?);
+                        let report = datastore.export_entries(&params, &mut writer).await?;
+                        writer.flush()?;
+                        (report, format!(" to {path}"))
+                    }
+                    None => {
+                        let mut stdout = std::io::stdout().lock();
+                        let report = datastore.export_entries(&params, &mut stdout).await?;
+                        (report, String::new())
+                    }
+                };
+                for err in &report.errors {
+                    eprintln!("failed to export {}/{}: {}", err.scope, err.key, err.error);
+                }
+                if !report.errors.is_empty() {
+                    anyhow::bail!(
+                        "exported {} entries{destination}; {} entries could not be read",
+                        report.exported,
+                        report.errors.len()
+                    );
+                }
+                Ok(output.map(|_| format!("exported {} entries{destination}", report.exported)))
+            }
+
+            DataStoreCommands::Diff {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                universe_id,
+                file,
+                other_datastore_name,
+                other_scope,
+                other_universe_id,
+                other_file,
+                other_api_key,
+                compare_scopes,
+                format,
+                pretty,
+                concurrency,
+                api_key,
+            } => {
+                let live_source = |name: Option<String>,
+                                   scope: Option<String>,
+                                   universe_id: Option<u64>,
+                                   api_key: Option<&String>|
+                 -> anyhow::Result<DiffSource> {
+                    let (Some(name), Some(universe_id)) = (name, universe_id) else {
+                        anyhow::bail!("a live source needs --datastore-name and --universe-id");
+                    };
+                    let Some(api_key) = api_key else {
+                        anyhow::bail!("a live source needs --api-key");
+                    };
+                    Ok(DiffSource::Live {
+                        datastore: RbxCloud::new(api_key).datastore(UniverseId(universe_id)),
+                        params: DataStoreExportEntries {
+                            name,
+                            scope,
+                            all_scopes,
+                            prefix: prefix.clone(),
+                            concurrency,
+                        },
+                    })
+                };
+                let old = match file {
+                    Some(path) => DiffSource::Dump(read_dump(BufReader::new(File::open(path)?))?),
+                    None => live_source(
+                        datastore_name.clone(),
+                        scope.clone(),
+                        universe_id,
+                        api_key.as_ref(),
+                    )?,
+                };
+                let new = match other_file {
+                    Some(path) => DiffSource::Dump(read_dump(BufReader::new(File::open(path)?))?),
+                    None => live_source(
+                        other_datastore_name.or(datastore_name),
+                        other_scope.or(scope),
+                        other_universe_id.or(universe_id),
+                        other_api_key.as_ref().or(api_key.as_ref()),
+                    )?,
+                };
+                let report = diff_entries(&old, &new, compare_scopes).await?;
+                let r = match format {
+                    DiffFormat::Human => format_diff(&report),
+                    DiffFormat::Json if pretty => serde_json::to_string_pretty(&report)?,
+                    DiffFormat::Json => serde_json::to_string(&report)?,
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::Validate {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                schema,
+                key_pattern,
+                concurrency,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id)).with_schema(
+                    &datastore_name,
+                    &key_pattern,
+                    JsonSchema::from_file(&schema)?,
+                );
+                let res = datastore
+                    .validate_entries(&DataStoreExportEntries {
+                        name: datastore_name,
+                        scope,
+                        all_scopes,
+                        prefix,
+                        concurrency,
+                    })
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                if res.invalid > 0 || res.unreadable > 0 {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "{} entries are invalid and {} could not be read",
+                        res.invalid,
+                        res.unreadable
+                    );
+                }
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::ProfileGet {
+                datastore_name,
+                scope,
+                key,
+                data_only,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let stored = datastore
+                    .get_profile(&DataStoreGetEntry {
+                        name: datastore_name,
+                        scope,
+                        key,
+                    })
+                    .await?;
+                let value = if data_only {
+                    stored.profile.data().clone()
+                } else {
+                    serde_json::to_value(stored.summary()?)?
+                };
+                let r = if pretty {
+                    serde_json::to_string_pretty(&value)?
+                } else {
+                    serde_json::to_string(&value)?
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::ProfileEdit {
+                datastore_name,
+                scope,
+                key,
+                data,
+                transform,
+                force,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let data = data.as_deref().map(serde_json::from_str).transpose()?;
+                let transform = transform.as_deref().map(Expression::parse).transpose()?;
+                let stored = datastore
+                    .update_profile(
+                        &DataStoreGetEntry {
+                            name: datastore_name,
+                            scope,
+                            key,
+                        },
+                        force,
+                        |profile| {
+                            let new_data = match (data, transform) {
+                                (Some(data), _) => data,
+                                (None, Some(expr)) => expr.evaluate(profile.data())?,
+                                (None, None) => unreachable!("clap requires --data or --transform"),
+                            };
+                            if new_data.is_null() {
+                                return Err(Error::InvalidProfile(
+                                    "refusing to set Data to null".to_string(),
+                                ));
+                            }
+                            profile.set_data(new_data);
+                            Ok(())
+                        },
+                    )
+                    .await?;
+                if let Some(session) = stored.profile.active_session() {
+                    eprintln!(
+                        "warning: profile is session-locked by {session}; the game server may overwrite this edit"
+                    );
+                }
+                let summary = stored.summary()?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&summary)?
+                } else {
+                    serde_json::to_string(&summary)?
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::ProfilePushUpdate {
+                datastore_name,
+                scope,
+                key,
+                data,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let id = datastore
+                    .push_global_update(
+                        &DataStoreGetEntry {
+                            name: datastore_name,
+                            scope,
+                            key,
+                        },
+                        serde_json::from_str(&data)?,
+                    )
+                    .await?;
+                let value = serde_json::json!({ "updateId": id });
+                let r = if pretty {
+                    serde_json::to_string_pretty(&value)?
+                } else {
+                    serde_json::to_string(&value)?
+                };
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::EraseUser {
+                user_id,
+                key_template,
+                ordered_key_template,
+                scope,
+                scan,
+                scan_datastore,
+                scan_ordered_datastore,
+                report,
+                dry_run,
+                yes,
+                concurrency,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let plan = datastore
+                    .plan_erasure(&DataStoreErasure {
+                        user_id: RobloxUserId(user_id),
+                        templates: parse_key_templates(key_template, &scope)?,
+                        ordered_templates: parse_key_templates(ordered_key_template, &scope)?,
+                        scan,
+                        scan_datastores: (!scan_datastore.is_empty()).then_some(scan_datastore),
+                        scan_ordered_datastores: scan_ordered_datastore
+                            .into_iter()
+                            .map(|name| ErasureScanTarget {
+                                name,
+                                scope: scope.clone(),
+                            })
+                            .collect(),
+                        concurrency,
+                    })
+                    .await?;
+                eprintln!("Erasing user {user_id}");
+                eprintln!("{}", summarize_erasure(&plan));
+
+                let result = if dry_run || plan.entries.is_empty() {
+                    plan
+                } else if yes || confirm("Delete these entries?")? {
+                    datastore.apply_erasure(&plan, concurrency).await?
+                } else {
+                    return Ok(Some("erasure cancelled".to_string()));
+                };
+
+                if let Some(path) = report {
+                    std::fs::write(&path, serde_json::to_string_pretty(&result)?)?;
+                }
+                let r = if pretty {
+                    serde_json::to_string_pretty(&result)?
+                } else {
+                    serde_json::to_string(&result)?
+                };
+                let failed = result.count(ErasureStatus::Failed);
+                if failed > 0 || !result.errors.is_empty() {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "erasure incomplete: {failed} entries failed and {} lookups failed; run it again",
+                        result.errors.len()
+                    );
+                }
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::Import {
+                datastore_name,
+                scope,
+                file,
+                skip_existing,
+                schema,
+                skip_preflight,
+                concurrency,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let mut datastore = rbx_cloud
+                    .datastore(UniverseId(universe_id))
+                    .with_preflight(!skip_preflight);
+                if let Some(schema) = schema {
+                    datastore.register_schema(
+                        &datastore_name,
+                        "*",
+                        JsonSchema::from_file(&schema)?,
+                    );
+                }
+                let entries = read_dump(BufReader::new(File::open(file)?))?;
+                let res = datastore
+                    .import_entries(
+                        &DataStoreImportEntries {
+                            name: datastore_name,
+                            scope,
+                            skip_existing,
+                            concurrency,
+                        },
+                        entries,
+                    )
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                if res.failed > 0 {
+                    println!("{r}");
+                    anyhow::bail!("{} entries failed to import", res.failed);
+                }
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::BulkDelete {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                keys_file,
+                all: _,
+                backup,
+                append,
+                yes,
+                concurrency,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let backup = backup.unwrap_or_else(|| {
+                    format!(
+                        "{datastore_name}-backup-{}.ndjson",
+                        Utc::now().format("%Y%m%dT%H%M%SZ")
+                    )
+                });
+                // Checked before anything is deleted; the file is created
+                // with `create_new` below in case it appears in the meantime.
+                if !append && Path::new(&backup).exists() {
+                    anyhow::bail!("{backup} already exists; pass --append to add to it");
+                }
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let datastore = rbx_cloud.datastore(UniverseId(universe_id));
+                let keys = match (prefix, keys_file) {
+                    (Some(prefix), _) => BulkDeleteKeys::Prefix(prefix),
+                    (None, Some(path)) => BulkDeleteKeys::Keys(
+                        std::fs::read_to_string(path)?
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                    ),
+                    (None, None) => BulkDeleteKeys::All,
+                };
+                let keys = datastore
+                    .collect_bulk_delete_keys(&DataStoreBulkDelete {
+                        name: datastore_name.clone(),
+                        scope,
+                        all_scopes,
+                        keys,
+                    })
+                    .await?;
+                if keys.is_empty() {
+                    return Ok(Some("no keys to delete".to_string()));
+                }
+
+                eprintln!("{} keys will be deleted from {datastore_name}:", keys.len());
+                for key in keys.iter().take(10) {
+                    eprintln!("  {}/{}", key.scope, key.key);
+                }
+                if keys.len() > 10 {
+                    eprintln!("  ... and {} more", keys.len() - 10);
+                }
+                if !yes && !confirm("Delete these keys?")? {
+                    return Ok(Some("bulk delete cancelled".to_string()));
+                }
+
+                let mut writer = BufWriter::new(open_output(&backup, append)?);
+                let res = datastore
+                    .bulk_delete(&datastore_name, keys, &mut writer, concurrency)
+                    .await;
+                eprintln!("backup written to {backup}");
+                let res = res?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                if res.failed > 0 {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "{} keys were not deleted, because they failed to back up or to delete",
+                        res.failed
+                    );
+                }
+                Ok(Some(r))
+            }
+
+            DataStoreCommands::Copy {
+                datastore_name,
+                scope,
+                all_scopes,
+                prefix,
+                universe_id,
+                dest_datastore_name,
+                dest_scope,
+                dest_universe_id,
+                dest_api_key,
+                transform,
+                skip_existing,
+                verify,
+                delete_source,
+                dry_run,
+                concurrency,
+                pretty,
+                api_key,
+            } => {
+                let transform = expression_transform(transform)?;
+                let source = RbxCloud::new(&api_key).datastore(UniverseId(universe_id));
+                let destination = RbxCloud::new(dest_api_key.as_deref().unwrap_or(&api_key))
+                    .datastore(UniverseId(dest_universe_id.unwrap_or(universe_id)));
+                let res = source
+                    .copy_entries(
+                        &destination,
+                        &DataStoreCopyEntries {
+                            destination_name: dest_datastore_name
+                                .unwrap_or_else(|| datastore_name.clone()),
+                            name: datastore_name,
+                            scope,
+                            all_scopes,
+                            prefix,
+                            destination_scope: dest_scope,
+                            transform,
+                            skip_existing,
+                            verify,
+                            delete_source,
+                            dry_run,
+                            concurrency,
+                        },
+                    )
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                if res.failed > 0 || res.verification_failures > 0 {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "{} entries failed to copy and {} failed verification",
+                        res.failed,
+                        res.verification_failures
+                    );
+                }
+                Ok(Some(r))
+            }
         }
     }
 }
@@ -0,0 +1,628 @@
+use clap::{Args, Subcommand};
+use rbxcloud::rbx::{
+    types::{RobloxUserId, UniverseId},
+    v2::{datastore::DataStoreEntryValue, Client},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::datastore_cli::parse_attributes;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum DataStoreV2Commands {
+    /// List DataStores in an experience
+    ListStores {
+        /// Max page size
+        #[clap(short = 's', long, value_parser)]
+        page_size: Option<u32>,
+
+        /// Next page token
+        #[clap(short, long, value_parser)]
+        token: Option<String>,
+
+        /// Filter (e.g. `id.startsWith("Player")`)
+        #[clap(short, long, value_parser)]
+        filter: Option<String>,
+
+        /// Include DataStores scheduled for deletion
+        #[clap(long, value_parser, default_value_t = false)]
+        show_deleted: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Schedule a DataStore for deletion
+    DeleteStore {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Restore a DataStore scheduled for deletion
+    UndeleteStore {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Take a snapshot of all DataStores in an experience
+    Snapshot {
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// List entries in a DataStore
+    List {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope (omit to list entries of all scopes)
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Max page size
+        #[clap(short = 'm', long, value_parser)]
+        page_size: Option<u32>,
+
+        /// Next page token
+        #[clap(short, long, value_parser)]
+        token: Option<String>,
+
+        /// Filter (e.g. `id.startsWith("Player")`)
+        #[clap(short, long, value_parser)]
+        filter: Option<String>,
+
+        /// Include deleted entries
+        #[clap(long, value_parser, default_value_t = false)]
+        show_deleted: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Get an entry
+    Get {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Get this revision instead of the latest one
+        #[clap(short, long, value_parser)]
+        revision: Option<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Create an entry, failing if it already exists
+    Create {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified data (up to 4MB)
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Associated UserID (can be multiple)
+        #[clap(short = 'U', long, value_parser)]
+        user_ids: Vec<u64>,
+
+        /// JSON-stringified attributes data
+        #[clap(short = 't', long, value_parser)]
+        attributes: Option<String>,
+
+        /// Attribute as KEY=VALUE, where VALUE is parsed as JSON or else used as a string (can be multiple)
+        #[clap(long, value_parser)]
+        attribute: Vec<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Update an entry
+    Update {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified data (up to 4MB)
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Associated UserID (can be multiple)
+        #[clap(short = 'U', long, value_parser)]
+        user_ids: Vec<u64>,
+
+        /// JSON-stringified attributes data
+        #[clap(short = 't', long, value_parser)]
+        attributes: Option<String>,
+
+        /// Attribute as KEY=VALUE, where VALUE is parsed as JSON or else used as a string (can be multiple)
+        #[clap(long, value_parser)]
+        attribute: Vec<String>,
+
+        /// Only update if the entry's current etag matches this
+        #[clap(short, long, value_parser)]
+        etag: Option<String>,
+
+        /// Create the entry if it does not exist
+        #[clap(long, value_parser, default_value_t = false)]
+        allow_missing: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Increment or create the value of an entry
+    Increment {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// The amount by which the entry should be incremented
+        #[clap(short, long, value_parser)]
+        increment_by: f64,
+
+        /// Associated UserID (can be multiple)
+        #[clap(short = 'U', long, value_parser)]
+        user_ids: Vec<u64>,
+
+        /// JSON-stringified attributes data
+        #[clap(short = 't', long, value_parser)]
+        attributes: Option<String>,
+
+        /// Attribute as KEY=VALUE, where VALUE is parsed as JSON or else used as a string (can be multiple)
+        #[clap(long, value_parser)]
+        attribute: Vec<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Delete an entry
+    Delete {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// List the revisions of an entry
+    ListRevisions {
+        /// DataStore ID
+        #[clap(short, long, value_parser)]
+        datastore_id: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Max page size
+        #[clap(short = 'm', long, value_parser)]
+        page_size: Option<u32>,
+
+        /// Next page token
+        #[clap(short, long, value_parser)]
+        token: Option<String>,
+
+        /// Filter (e.g. `revisionCreateTime >= 2024-01-01T00:00:00Z`)
+        #[clap(short, long, value_parser)]
+        filter: Option<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct DataStoreV2 {
+    #[clap(subcommand)]
+    command: DataStoreV2Commands,
+}
+
+impl DataStoreV2 {
+    pub(crate) async fn run(self) -> anyhow::Result<Option<String>> {
+        match self.command {
+            DataStoreV2Commands::ListStores {
+                page_size,
+                token,
+                filter,
+                show_deleted,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .list_data_stores(page_size, filter, token, Some(show_deleted))
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::DeleteStore {
+                datastore_id,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client.delete_data_store(&datastore_id).await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::UndeleteStore {
+                datastore_id,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client.undelete_data_store(&datastore_id).await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Snapshot {
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client.snapshot().await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::List {
+                datastore_id,
+                scope,
+                page_size,
+                token,
+                filter,
+                show_deleted,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .list_entries(
+                        &datastore_id,
+                        scope,
+                        page_size,
+                        filter,
+                        token,
+                        Some(show_deleted),
+                    )
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Get {
+                datastore_id,
+                scope,
+                key,
+                revision,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .get_entry(&datastore_id, scope, &key, revision)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Create {
+                datastore_id,
+                scope,
+                key,
+                data,
+                user_ids,
+                attributes,
+                attribute,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let entry = DataStoreEntryValue {
+                    value: serde_json::from_str::<Value>(&data)?,
+                    users: user_ids.into_iter().map(RobloxUserId).collect(),
+                    attributes: parse_attributes(attributes, attribute)?,
+                    etag: None,
+                };
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .create_entry(&datastore_id, scope, &key, entry)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Update {
+                datastore_id,
+                scope,
+                key,
+                data,
+                user_ids,
+                attributes,
+                attribute,
+                etag,
+                allow_missing,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let entry = DataStoreEntryValue {
+                    value: serde_json::from_str::<Value>(&data)?,
+                    users: user_ids.into_iter().map(RobloxUserId).collect(),
+                    attributes: parse_attributes(attributes, attribute)?,
+                    etag,
+                };
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .update_entry(&datastore_id, scope, &key, entry, Some(allow_missing))
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Increment {
+                datastore_id,
+                scope,
+                key,
+                increment_by,
+                user_ids,
+                attributes,
+                attribute,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .increment_entry(
+                        &datastore_id,
+                        scope,
+                        &key,
+                        increment_by,
+                        user_ids.into_iter().map(RobloxUserId).collect(),
+                        parse_attributes(attributes, attribute)?,
+                    )
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::Delete {
+                datastore_id,
+                scope,
+                key,
+                universe_id,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .delete_entry(&datastore_id, scope, &key)
+                    .await;
+                match res {
+                    Ok(_) => Ok(None),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            DataStoreV2Commands::ListRevisions {
+                datastore_id,
+                scope,
+                key,
+                page_size,
+                token,
+                filter,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let datastore_client = client.datastore(UniverseId(universe_id));
+                let res = datastore_client
+                    .list_entry_revisions(&datastore_id, scope, &key, page_size, filter, token)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+        }
+    }
+}
+
+fn to_json<T: Serialize>(data: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(data)
+    } else {
+        serde_json::to_string(data)
+    }
+}
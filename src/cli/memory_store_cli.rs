@@ -0,0 +1,614 @@
+use std::time::{Duration, Instant};
+
+use clap::{Args, Subcommand};
+use rbxcloud::rbx::{
+    types::UniverseId,
+    v2::{
+        memory_store::{parse_ttl, OperationError, SortKey, SortedMapItemValue, SortedMapOrder},
+        Client,
+    },
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::confirm;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum SortedMapCommands {
+    /// List items in a sorted map
+    List {
+        /// Sorted map ID
+        #[clap(short, long, value_parser)]
+        map_id: String,
+
+        /// Max page size
+        #[clap(short = 's', long, value_parser)]
+        page_size: Option<u32>,
+
+        /// Next page token
+        #[clap(short, long, value_parser)]
+        token: Option<String>,
+
+        /// Filter (e.g. `id > "player_100" && sortKey < 50`)
+        #[clap(short, long, value_parser)]
+        filter: Option<String>,
+
+        /// List items in descending order
+        #[clap(long, value_parser, default_value_t = false)]
+        desc: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Get an item
+    Get {
+        /// Sorted map ID
+        #[clap(short, long, value_parser)]
+        map_id: String,
+
+        /// The ID of the item
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Create an item, failing if it already exists
+    Create {
+        /// Sorted map ID
+        #[clap(short, long, value_parser)]
+        map_id: String,
+
+        /// The ID of the item
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified data
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Time to live (e.g. `30s`, `10m`, `2h`, `1d`; at most 45 days)
+        #[clap(long, value_parser)]
+        ttl: String,
+
+        /// String sort key
+        #[clap(long, value_parser, conflicts_with = "numeric_sort_key")]
+        sort_key: Option<String>,
+
+        /// Numeric sort key
+        #[clap(long, value_parser, allow_negative_numbers = true)]
+        numeric_sort_key: Option<f64>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Update an item, replacing its value, TTL and sort key
+    Update {
+        /// Sorted map ID
+        #[clap(short, long, value_parser)]
+        map_id: String,
+
+        /// The ID of the item
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// JSON-stringified data
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Time to live (e.g. `30s`, `10m`, `2h`, `1d`; at most 45 days)
+        #[clap(long, value_parser)]
+        ttl: String,
+
+        /// String sort key
+        #[clap(long, value_parser, conflicts_with = "numeric_sort_key")]
+        sort_key: Option<String>,
+
+        /// Numeric sort key
+        #[clap(long, value_parser, allow_negative_numbers = true)]
+        numeric_sort_key: Option<f64>,
+
+        /// Only update if the item's current etag matches this
+        #[clap(short, long, value_parser)]
+        etag: Option<String>,
+
+        /// Create the item if it does not exist
+        #[clap(long, value_parser, default_value_t = false)]
+        allow_missing: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Delete an item
+    Delete {
+        /// Sorted map ID
+        #[clap(short, long, value_parser)]
+        map_id: String,
+
+        /// The ID of the item
+        #[clap(short, long, value_parser)]
+        key: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SortedMap {
+    #[clap(subcommand)]
+    command: SortedMapCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum QueueCommands {
+    /// Add an item to a queue
+    Enqueue {
+        /// Queue ID
+        #[clap(short, long, value_parser)]
+        queue_id: String,
+
+        /// JSON-stringified data
+        #[clap(short = 'D', long, value_parser)]
+        data: String,
+
+        /// Priority (items with a higher priority are read first)
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = 0.0,
+            allow_negative_numbers = true
+        )]
+        priority: f64,
+
+        /// Time to live (e.g. `30s`, `10m`, `2h`, `1d`; at most 45 days)
+        #[clap(long, value_parser)]
+        ttl: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Read items from a queue, hiding them from other readers
+    Read {
+        /// Queue ID
+        #[clap(short, long, value_parser)]
+        queue_id: String,
+
+        /// Number of items to read
+        #[clap(short, long, value_parser, default_value_t = 1)]
+        count: u32,
+
+        /// Read nothing unless `count` items are available
+        #[clap(long, value_parser, default_value_t = false)]
+        all_or_nothing: bool,
+
+        /// How long read items are hidden from other readers (e.g. `30s`, `5m`)
+        #[clap(short, long, value_parser)]
+        invisibility_window: Option<String>,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Remove the items of a read from a queue
+    Discard {
+        /// Queue ID
+        #[clap(short, long, value_parser)]
+        queue_id: String,
+
+        /// Read ID returned by `read`
+        #[clap(short, long, value_parser)]
+        read_id: String,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct Queue {
+    #[clap(subcommand)]
+    command: QueueCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum MemoryStoreCommands {
+    /// Access MemoryStore sorted maps
+    SortedMap(SortedMap),
+
+    /// Access MemoryStore queues
+    Queue(Queue),
+
+    /// Delete every sorted map and queue of an experience
+    Flush {
+        /// Wait for this flush operation instead of starting a new flush
+        #[clap(short, long, value_parser)]
+        operation_id: Option<String>,
+
+        /// Return as soon as the flush is started
+        #[clap(long, value_parser, default_value_t = false)]
+        no_wait: bool,
+
+        /// Seconds between two polls of the flush operation
+        #[clap(long, value_parser, default_value_t = 5)]
+        poll_interval: u64,
+
+        /// Stop waiting after this many seconds, and exit with an error if the flush is still running
+        #[clap(long, value_parser)]
+        timeout: Option<u64>,
+
+        /// Skip the confirmation prompt
+        #[clap(short, long, value_parser, default_value_t = false)]
+        yes: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct MemoryStore {
+    #[clap(subcommand)]
+    command: MemoryStoreCommands,
+}
+
+impl MemoryStore {
+    pub(crate) async fn run(self) -> anyhow::Result<Option<String>> {
+        match self.command {
+            MemoryStoreCommands::SortedMap(command) => command.run().await,
+            MemoryStoreCommands::Queue(command) => command.run().await,
+
+            MemoryStoreCommands::Flush {
+                operation_id,
+                no_wait,
+                poll_interval,
+                timeout,
+                yes,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let started = Instant::now();
+                let mut operation = match operation_id {
+                    Some(operation_id) => memory_store.get_flush_operation(&operation_id).await,
+                    None => {
+                        let prompt = format!(
+                            "Delete every MemoryStore sorted map and queue of universe {universe_id}? This cannot be undone."
+                        );
+                        if !yes && !confirm(&prompt)? {
+                            return Ok(Some("flush cancelled".to_string()));
+                        }
+                        memory_store.flush().await
+                    }
+                }
+                .map_err(|err| anyhow::anyhow!(err))?;
+                if !no_wait && !operation.done {
+                    eprintln!("waiting for flush {}...", operation.operation_id());
+                    operation = memory_store
+                        .wait_for_flush(
+                            operation.operation_id(),
+                            Duration::from_secs(poll_interval.max(1)),
+                            timeout.map(Duration::from_secs),
+                        )
+                        .await
+                        .map_err(|err| anyhow::anyhow!(err))?;
+                }
+                let report = FlushReport {
+                    operation_id: operation.operation_id().to_string(),
+                    done: operation.done,
+                    succeeded: operation.succeeded(),
+                    error: operation.error.clone(),
+                    elapsed_seconds: started.elapsed().as_secs_f64(),
+                };
+                let json = to_json(&report, pretty)?;
+                if let Some(error) = &operation.error {
+                    println!("{json}");
+                    anyhow::bail!("flush failed: {}", error.message);
+                }
+                if !operation.done && !no_wait {
+                    println!("{json}");
+                    anyhow::bail!(
+                        "flush is still running after the timeout; wait again with --operation-id {}",
+                        report.operation_id
+                    );
+                }
+                Ok(Some(json))
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlushReport {
+    operation_id: String,
+    done: bool,
+    succeeded: bool,
+    error: Option<OperationError>,
+    elapsed_seconds: f64,
+}
+
+fn sort_key(string_key: Option<String>, numeric_key: Option<f64>) -> Option<SortKey> {
+    match (string_key, numeric_key) {
+        (Some(key), _) => Some(SortKey::String(key)),
+        (None, Some(key)) => Some(SortKey::Numeric(key)),
+        (None, None) => None,
+    }
+}
+
+impl SortedMap {
+    async fn run(self) -> anyhow::Result<Option<String>> {
+        match self.command {
+            SortedMapCommands::List {
+                map_id,
+                page_size,
+                token,
+                filter,
+                desc,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let order_by = desc.then_some(SortedMapOrder::Descending);
+                let res = memory_store
+                    .list_sorted_map_items(&map_id, page_size, order_by, filter, token)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            SortedMapCommands::Get {
+                map_id,
+                key,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store.get_sorted_map_item(&map_id, &key).await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            SortedMapCommands::Create {
+                map_id,
+                key,
+                data,
+                ttl,
+                sort_key: string_sort_key,
+                numeric_sort_key,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let item = SortedMapItemValue {
+                    value: serde_json::from_str::<Value>(&data)?,
+                    ttl: parse_ttl(&ttl)?,
+                    sort_key: sort_key(string_sort_key, numeric_sort_key),
+                    etag: None,
+                };
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store
+                    .create_sorted_map_item(&map_id, &key, item)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            SortedMapCommands::Update {
+                map_id,
+                key,
+                data,
+                ttl,
+                sort_key: string_sort_key,
+                numeric_sort_key,
+                etag,
+                allow_missing,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let item = SortedMapItemValue {
+                    value: serde_json::from_str::<Value>(&data)?,
+                    ttl: parse_ttl(&ttl)?,
+                    sort_key: sort_key(string_sort_key, numeric_sort_key),
+                    etag,
+                };
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store
+                    .update_sorted_map_item(&map_id, &key, item, Some(allow_missing))
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            SortedMapCommands::Delete {
+                map_id,
+                key,
+                universe_id,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store.delete_sorted_map_item(&map_id, &key).await;
+                match res {
+                    Ok(_) => Ok(None),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+        }
+    }
+}
+
+impl Queue {
+    async fn run(self) -> anyhow::Result<Option<String>> {
+        match self.command {
+            QueueCommands::Enqueue {
+                queue_id,
+                data,
+                priority,
+                ttl,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let data = serde_json::from_str::<Value>(&data)?;
+                let ttl = parse_ttl(&ttl)?;
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store
+                    .enqueue_item(&queue_id, data, priority, ttl)
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            QueueCommands::Read {
+                queue_id,
+                count,
+                all_or_nothing,
+                invisibility_window,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let invisibility_window = match invisibility_window {
+                    Some(window) => Some(parse_ttl(&window)?),
+                    None => None,
+                };
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store
+                    .read_queue_items(
+                        &queue_id,
+                        Some(count),
+                        Some(all_or_nothing),
+                        invisibility_window,
+                    )
+                    .await;
+                match res {
+                    Ok(data) => Ok(Some(to_json(&data, pretty)?)),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+
+            QueueCommands::Discard {
+                queue_id,
+                read_id,
+                universe_id,
+                api_key,
+            } => {
+                let client = Client::new(&api_key);
+                let memory_store = client.memory_store(UniverseId(universe_id));
+                let res = memory_store.discard_queue_items(&queue_id, &read_id).await;
+                match res {
+                    Ok(_) => Ok(None),
+                    Err(err) => Err(anyhow::anyhow!(err)),
+                }
+            }
+        }
+    }
+}
+
+fn to_json<T: Serialize>(data: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(data)
+    } else {
+        serde_json::to_string(data)
+    }
+}
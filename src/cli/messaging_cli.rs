@@ -1,6 +1,17 @@
+use std::time::Duration;
+
 use clap::{Args, Subcommand};
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, BufReader};
 
-use rbxcloud::rbx::{types::UniverseId, v1::RbxCloud};
+use rbxcloud::rbx::{
+    types::UniverseId,
+    v1::{
+        messaging_batch::DEFAULT_MAX_REQUESTS_PER_MINUTE,
+        messaging_broadcast::{BroadcastConfig, BroadcastMessage},
+        RbxCloud,
+    },
+};
 
 #[derive(Debug, Subcommand)]
 pub enum MessagingCommands {
@@ -11,13 +22,80 @@ pub enum MessagingCommands {
         topic: String,
 
         /// Message to send
-        #[clap(short, long, value_parser)]
-        message: String,
+        #[clap(
+            short,
+            long,
+            value_parser,
+            required_unless_present = "stdin",
+            conflicts_with = "stdin"
+        )]
+        message: Option<String>,
+
+        /// Read messages from stdin, one per line
+        #[clap(long, value_parser, default_value_t = false)]
+        stdin: bool,
+
+        /// Maximum messages published per minute when reading from stdin
+        #[clap(long, value_parser, default_value_t = DEFAULT_MAX_REQUESTS_PER_MINUTE)]
+        max_requests_per_minute: u32,
 
         /// Universe ID of the experience
         #[clap(short, long, value_parser)]
         universe_id: u64,
 
+        /// Pretty-print the JSON report when reading from stdin
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Publish a message to many universes at once
+    Broadcast {
+        /// Message topic
+        #[clap(short, long, value_parser)]
+        topic: String,
+
+        /// Message to send
+        #[clap(short, long, value_parser)]
+        message: String,
+
+        /// Comma-separated list of universe IDs
+        #[clap(
+            short,
+            long,
+            value_parser,
+            value_delimiter = ',',
+            required_unless_present = "group"
+        )]
+        universe_ids: Vec<u64>,
+
+        /// Name of a universe group in the config file
+        #[clap(short, long, value_parser, requires = "config")]
+        group: Option<String>,
+
+        /// Path to a JSON config file defining universe groups
+        #[clap(short, long, value_parser, env = "RBXCLOUD_CONFIG")]
+        config: Option<String>,
+
+        /// Maximum number of universes published to at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Number of times a failed publish is retried
+        #[clap(short, long, value_parser, default_value_t = 0)]
+        retries: u32,
+
+        /// Seconds to wait before each retry
+        #[clap(long, value_parser, default_value_t = 2)]
+        retry_delay: u64,
+
+        /// Pretty-print the JSON report
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
         /// Roblox Open Cloud API Key
         #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
         api_key: String,
@@ -36,17 +114,97 @@ impl Messaging {
             MessagingCommands::Publish {
                 topic,
                 message,
+                stdin,
+                max_requests_per_minute,
                 universe_id,
+                pretty,
                 api_key,
             } => {
                 let rbx_cloud = RbxCloud::new(&api_key);
                 let messaging = rbx_cloud.messaging(UniverseId(universe_id), &topic);
+                if stdin {
+                    let mut publisher = messaging.batch_publisher(max_requests_per_minute);
+                    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+                    while let Some(line) = lines.next_line().await? {
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        // Failures are recorded in the report.
+                        let _ = publisher.publish(&line).await;
+                    }
+                    let report = publisher.into_report();
+                    let json = to_json(&report, pretty)?;
+                    if report.failed > 0 {
+                        println!("{json}");
+                        anyhow::bail!(
+                            "failed to publish {} of {} messages",
+                            report.failed,
+                            report.published + report.failed
+                        );
+                    }
+                    return Ok(Some(json));
+                }
+                let message = message.unwrap_or_default();
                 let res = messaging.publish(&message).await;
                 match res {
                     Ok(()) => Ok(Some(format!("published message to topic {topic}"))),
                     Err(err) => Err(anyhow::anyhow!(err)),
                 }
             }
+
+            MessagingCommands::Broadcast {
+                topic,
+                message,
+                universe_ids,
+                group,
+                config,
+                concurrency,
+                retries,
+                retry_delay,
+                pretty,
+                api_key,
+            } => {
+                let mut universe_ids: Vec<UniverseId> =
+                    universe_ids.into_iter().map(UniverseId).collect();
+                if let Some(group) = group {
+                    let config = BroadcastConfig::from_file(&config.unwrap_or_default())?;
+                    universe_ids.extend_from_slice(config.group(&group)?);
+                }
+                let Some(&universe_id) = universe_ids.first() else {
+                    anyhow::bail!("no universes to publish to");
+                };
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let messaging = rbx_cloud.messaging(universe_id, &topic);
+                let report = messaging
+                    .broadcast(
+                        &message,
+                        &BroadcastMessage {
+                            universe_ids,
+                            concurrency,
+                            retries,
+                            retry_delay: Duration::from_secs(retry_delay),
+                        },
+                    )
+                    .await?;
+                let json = to_json(&report, pretty)?;
+                if report.failed > 0 {
+                    println!("{json}");
+                    anyhow::bail!(
+                        "failed to publish to {} of {} universes",
+                        report.failed,
+                        report.results.len()
+                    );
+                }
+                Ok(Some(json))
+            }
         }
     }
 }
+
+fn to_json<T: Serialize>(data: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(data)
+    } else {
+        serde_json::to_string(data)
+    }
+}
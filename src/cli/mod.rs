@@ -1,9 +1,11 @@
 mod assets_cli;
 mod datastore_cli;
+mod datastore_v2_cli;
 mod experience_cli;
 mod group_cli;
 mod inventory_cli;
 mod luau_execution_cli;
+mod memory_store_cli;
 mod messaging_cli;
 mod notification_cli;
 mod ordered_datastore_cli;
@@ -13,9 +15,13 @@ mod universe_cli;
 mod user_cli;
 mod user_restriction_cli;
 
+use std::io::{self, BufRead, Write};
+
 use clap::{Parser, Subcommand};
+use datastore_v2_cli::DataStoreV2;
 use inventory_cli::Inventory;
 use luau_execution_cli::Luau;
+use memory_store_cli::MemoryStore;
 use universe_cli::Universe;
 use user_cli::User;
 use user_restriction_cli::UserRestriction;
@@ -47,6 +53,9 @@ pub(crate) enum Command {
     /// Access the Roblox DataStore API
     Datastore(DataStore),
 
+    /// Access the Roblox Cloud v2 DataStore API
+    DatastoreV2(DataStoreV2),
+
     /// Access the Roblox OrderedDataStore API
     OrderedDatastore(OrderedDataStore),
 
@@ -58,6 +67,9 @@ pub(crate) enum Command {
 
     Luau(Luau),
 
+    /// Access the Roblox MemoryStore API
+    MemoryStore(MemoryStore),
+
     /// Access the Roblox Subscription API
     Subscription(Subscription),
 
@@ -84,10 +96,12 @@ impl Cli {
             Command::Experience(command) => command.run().await,
             Command::Messaging(command) => command.run().await,
             Command::Datastore(command) => command.run().await,
+            Command::DatastoreV2(command) => command.run().await,
             Command::OrderedDatastore(command) => command.run().await,
             Command::Group(command) => command.run().await,
             Command::Inventory(command) => command.run().await,
             Command::Luau(command) => command.run().await,
+            Command::MemoryStore(command) => command.run().await,
             Command::Subscription(command) => command.run().await,
             Command::Notification(command) => command.run().await,
             Command::Place(command) => command.run().await,
@@ -97,3 +111,14 @@ impl Cli {
         }
     }
 }
+
+/// Ask the user to confirm an action on stdin. Anything other than `y` or
+/// `yes` is treated as a refusal.
+pub(crate) fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    eprint!("{prompt} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
@@ -1,7 +1,24 @@
-use clap::{Args, Subcommand};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use clap::{Args, Subcommand, ValueEnum};
+
+use super::confirm;
 use rbxcloud::rbx::{
     types::UniverseId,
     v1::{
+        ordered_datastore::{OrderBy, OrderedFilter},
+        ordered_datastore_import::{
+            read_import_rows, OrderedDataStoreImportEntries, OrderedImportFormat, OrderedImportMode,
+        },
+        ordered_datastore_leaderboard::{LeaderboardFormat, OrderedDataStoreLeaderboard},
+        ordered_datastore_rollover::{
+            OrderedDataStoreRollover, RolloverProgress, RolloverReset, RolloverState,
+        },
         OrderedDataStoreCreateEntry, OrderedDataStoreEntry, OrderedDataStoreIncrementEntry,
         OrderedDataStoreListEntries, OrderedDataStoreUpdateEntry, RbxCloud,
     },
@@ -28,13 +45,25 @@ pub enum OrderedDataStoreCommands {
         page_token: Option<String>,
 
         /// The enumeration direction (Use 'desc' for descending)
-        #[clap(short, long, value_parser)]
+        #[clap(short, long, value_parser, conflicts_with = "desc")]
         order_by: Option<String>,
 
-        /// A range of qualifying values of entries to return
-        #[clap(short, long, value_parser)]
+        /// List entries in descending order
+        #[clap(long, value_parser, default_value_t = false)]
+        desc: bool,
+
+        /// A range of qualifying values of entries to return (e.g. 'entry >= 10 && entry <= 50')
+        #[clap(short, long, value_parser, conflicts_with_all = ["min", "max"])]
         filter: Option<String>,
 
+        /// Only list entries with a value of at least this
+        #[clap(long, value_parser, allow_negative_numbers = true)]
+        min: Option<i64>,
+
+        /// Only list entries with a value of at most this
+        #[clap(long, value_parser, allow_negative_numbers = true)]
+        max: Option<i64>,
+
         /// Universe ID of the experience
         #[clap(short, long, value_parser)]
         universe_id: u64,
@@ -194,6 +223,273 @@ pub enum OrderedDataStoreCommands {
         #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
         api_key: String,
     },
+
+    /// Get the top entries of a leaderboard with their ranks
+    Top {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Number of entries to return
+        #[clap(short = 'n', long, value_parser, default_value_t = 10)]
+        count: usize,
+
+        /// Rank lower values first (e.g. for best times)
+        #[clap(long, value_parser, default_value_t = false)]
+        ascending: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Get the leaderboard rank of an entry
+    Rank {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        id: String,
+
+        /// Rank lower values first (e.g. for best times)
+        #[clap(long, value_parser, default_value_t = false)]
+        ascending: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Get an entry with the entries ranked right above and below it
+    Around {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// The ID of the entry
+        #[clap(short, long, value_parser)]
+        id: String,
+
+        /// Number of entries to return above and below the entry
+        #[clap(short = 'n', long, value_parser, default_value_t = 5)]
+        count: usize,
+
+        /// Rank lower values first (e.g. for best times)
+        #[clap(long, value_parser, default_value_t = false)]
+        ascending: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Export a leaderboard as CSV or JSON
+    Export {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t = LeaderboardExportFormat::Csv)]
+        format: LeaderboardExportFormat,
+
+        /// Only export the top entries
+        #[clap(short, long, value_parser)]
+        limit: Option<usize>,
+
+        /// File to write to (defaults to stdout)
+        #[clap(short, long, value_parser)]
+        output: Option<String>,
+
+        /// Rank lower values first (e.g. for best times)
+        #[clap(long, value_parser, default_value_t = false)]
+        ascending: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Archive a leaderboard to another name or scope and reset it
+    Rollover {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// Archive DataStore name (defaults to the source name)
+        #[clap(long, value_parser)]
+        archive_name: Option<String>,
+
+        /// Archive scope (defaults to the source scope)
+        #[clap(long, value_parser)]
+        archive_scope: Option<String>,
+
+        /// What to do with the original entries once archived
+        #[clap(short, long, value_enum, default_value_t = RolloverResetMode::Delete)]
+        reset: RolloverResetMode,
+
+        /// File recording progress; an existing file resumes the rollover
+        #[clap(long, value_parser)]
+        state_file: Option<String>,
+
+        /// Maximum number of entries written at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Skip the confirmation prompt
+        #[clap(short, long, value_parser, default_value_t = false)]
+        yes: bool,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+
+    /// Import entries from a CSV or NDJSON file
+    Import {
+        /// DataStore name
+        #[clap(short, long, value_parser)]
+        datastore_name: String,
+
+        /// DataStore scope
+        #[clap(short, long, value_parser)]
+        scope: Option<String>,
+
+        /// CSV or NDJSON file of `id` and `value` pairs
+        #[clap(short, long, value_parser)]
+        file: String,
+
+        /// Input format (defaults to csv for .csv files, ndjson otherwise)
+        #[clap(long, value_enum)]
+        format: Option<ImportFileFormat>,
+
+        /// Set entries to the imported values, or add the imported values to them
+        #[clap(short, long, value_enum, default_value_t = ImportMode::Upsert)]
+        mode: ImportMode,
+
+        /// Maximum number of entries written at once
+        #[clap(long, value_parser, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Maximum number of requests sent per minute (0 for no limit)
+        #[clap(long, value_parser, default_value_t = 0)]
+        max_requests_per_minute: u32,
+
+        /// Universe ID of the experience
+        #[clap(short, long, value_parser)]
+        universe_id: u64,
+
+        /// Pretty-print the JSON response
+        #[clap(short, long, value_parser, default_value_t = false)]
+        pretty: bool,
+
+        /// Roblox Open Cloud API Key
+        #[clap(short, long, value_parser, env = "RBXCLOUD_API_KEY")]
+        api_key: String,
+    },
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum LeaderboardExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ImportFileFormat {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ImportMode {
+    Upsert,
+    Increment,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum RolloverResetMode {
+    Delete,
+    Zero,
+}
+
+fn write_rollover_state(path: &str, state: &RolloverState) -> anyhow::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+fn leaderboard(
+    name: String,
+    scope: Option<String>,
+    ascending: bool,
+) -> OrderedDataStoreLeaderboard {
+    OrderedDataStoreLeaderboard {
+        name,
+        scope,
+        order_by: if ascending {
+            OrderBy::Ascending
+        } else {
+            OrderBy::Descending
+        },
+    }
 }
 
 #[derive(Debug, Args)]
@@ -211,11 +507,23 @@ impl OrderedDataStore {
                 max_page_size,
                 page_token,
                 order_by,
+                desc,
                 filter,
+                min,
+                max,
                 universe_id,
                 pretty,
                 api_key,
             } => {
+                let order_by = match order_by {
+                    Some(order_by) => Some(order_by.parse::<OrderBy>()?),
+                    None => desc.then_some(OrderBy::Descending),
+                };
+                let filter = match filter {
+                    Some(filter) => Some(filter.parse::<OrderedFilter>()?),
+                    None if min.is_some() || max.is_some() => Some(OrderedFilter { min, max }),
+                    None => None,
+                };
                 let rbx_cloud = RbxCloud::new(&api_key);
                 let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
                 let res = ordered_datastore
@@ -390,6 +698,244 @@ impl OrderedDataStore {
                     Err(err) => Err(err.into()),
                 }
             }
+
+            OrderedDataStoreCommands::Top {
+                datastore_name,
+                scope,
+                count,
+                ascending,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let res = ordered_datastore
+                    .top_n(&leaderboard(datastore_name, scope, ascending), count)
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                Ok(Some(r))
+            }
+
+            OrderedDataStoreCommands::Rank {
+                datastore_name,
+                scope,
+                id,
+                ascending,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let res = ordered_datastore
+                    .rank_of(&leaderboard(datastore_name, scope, ascending), &id)
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                Ok(Some(r))
+            }
+
+            OrderedDataStoreCommands::Around {
+                datastore_name,
+                scope,
+                id,
+                count,
+                ascending,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let res = ordered_datastore
+                    .around(&leaderboard(datastore_name, scope, ascending), &id, count)
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                Ok(Some(r))
+            }
+
+            OrderedDataStoreCommands::Export {
+                datastore_name,
+                scope,
+                format,
+                limit,
+                output,
+                ascending,
+                universe_id,
+                api_key,
+            } => {
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let leaderboard = leaderboard(datastore_name, scope, ascending);
+                let format = match format {
+                    LeaderboardExportFormat::Csv => LeaderboardFormat::Csv,
+                    LeaderboardExportFormat::Json => LeaderboardFormat::Json,
+                };
+                match output {
+                    Some(path) => {
+                        let mut writer = BufWriter::new(File::create(&path)?);
+                        let count = ordered_datastore
+                            .export_leaderboard(&leaderboard, limit, format, &mut writer)
+                            .await?;
+                        writer.flush()?;
+                        Ok(Some(format!("exported {count} entries to {path}")))
+                    }
+                    None => {
+                        let mut stdout = std::io::stdout().lock();
+                        ordered_datastore
+                            .export_leaderboard(&leaderboard, limit, format, &mut stdout)
+                            .await?;
+                        Ok(None)
+                    }
+                }
+            }
+
+            OrderedDataStoreCommands::Rollover {
+                datastore_name,
+                scope,
+                archive_name,
+                archive_scope,
+                reset,
+                state_file,
+                concurrency,
+                yes,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let resume = match &state_file {
+                    Some(path) if Path::new(path).exists() => {
+                        Some(serde_json::from_str::<RolloverState>(&fs::read_to_string(
+                            path,
+                        )?)?)
+                    }
+                    _ => None,
+                };
+                let prompt = match &resume {
+                    Some(state) => format!(
+                        "Resume rollover of {}/{} to {}/{}?",
+                        state.name, state.scope, state.archive_name, state.archive_scope
+                    ),
+                    None => format!("Archive and reset {datastore_name}?"),
+                };
+                if !yes && !confirm(&prompt)? {
+                    return Ok(None);
+                }
+                let progress_file = state_file.clone();
+                let progress: RolloverProgress = Arc::new(move |state| {
+                    eprintln!(
+                        "{:?}: {} read, {} archived, {} reset, {} failed",
+                        state.phase,
+                        state.source_count,
+                        state.archived,
+                        state.reset,
+                        state.failures.len()
+                    );
+                    if let Some(path) = &progress_file {
+                        if let Err(err) = write_rollover_state(path, state) {
+                            eprintln!("failed to write {path}: {err}");
+                        }
+                    }
+                });
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let state = ordered_datastore
+                    .rollover(OrderedDataStoreRollover {
+                        name: datastore_name,
+                        scope,
+                        archive_name,
+                        archive_scope,
+                        reset: match reset {
+                            RolloverResetMode::Delete => RolloverReset::Delete,
+                            RolloverResetMode::Zero => RolloverReset::Zero,
+                        },
+                        concurrency,
+                        resume,
+                        progress: Some(progress),
+                    })
+                    .await?;
+                if let Some(path) = &state_file {
+                    write_rollover_state(path, &state)?;
+                }
+                let r = if pretty {
+                    serde_json::to_string_pretty(&state)?
+                } else {
+                    serde_json::to_string(&state)?
+                };
+                if state.verified == Some(false) {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "archive has {} entries but {} were read from the source; the source was not reset",
+                        state.archive_count.unwrap_or_default(),
+                        state.source_count
+                    );
+                }
+                Ok(Some(r))
+            }
+
+            OrderedDataStoreCommands::Import {
+                datastore_name,
+                scope,
+                file,
+                format,
+                mode,
+                concurrency,
+                max_requests_per_minute,
+                universe_id,
+                pretty,
+                api_key,
+            } => {
+                let format = match format {
+                    Some(ImportFileFormat::Csv) => OrderedImportFormat::Csv,
+                    Some(ImportFileFormat::Ndjson) => OrderedImportFormat::Ndjson,
+                    None if file.to_lowercase().ends_with(".csv") => OrderedImportFormat::Csv,
+                    None => OrderedImportFormat::Ndjson,
+                };
+                let rows = read_import_rows(BufReader::new(File::open(&file)?), format)?;
+                let rbx_cloud = RbxCloud::new(&api_key);
+                let ordered_datastore = rbx_cloud.ordered_datastore(UniverseId(universe_id));
+                let res = ordered_datastore
+                    .import_entries(
+                        OrderedDataStoreImportEntries {
+                            name: datastore_name,
+                            scope,
+                            mode: match mode {
+                                ImportMode::Upsert => OrderedImportMode::Upsert,
+                                ImportMode::Increment => OrderedImportMode::Increment,
+                            },
+                            concurrency,
+                            max_requests_per_minute,
+                        },
+                        rows,
+                    )
+                    .await?;
+                let r = if pretty {
+                    serde_json::to_string_pretty(&res)?
+                } else {
+                    serde_json::to_string(&res)?
+                };
+                if res.invalid > 0 || res.failed > 0 {
+                    println!("{r}");
+                    anyhow::bail!(
+                        "{} rows are invalid and {} failed to import",
+                        res.invalid,
+                        res.failed
+                    );
+                }
+                Ok(Some(r))
+            }
         }
     }
 }
@@ -154,10 +154,7 @@ impl UserRestriction {
                 let client = Client::new(&api_key);
                 let user_restriction_client = client.user_restriction(UniverseId(universe_id));
                 let res = user_restriction_client
-                    .get_user_restriction(
-                        RobloxUserId(user_id),
-                        place_id.and_then(|id| Some(PlaceId(id))),
-                    )
+                    .get_user_restriction(RobloxUserId(user_id), place_id.map(PlaceId))
                     .await;
                 match res {
                     Ok(info) => {
@@ -189,7 +186,7 @@ impl UserRestriction {
                 let res = user_restriction_client
                     .update_user_restriction(&UserRestrictionParams {
                         user_id: RobloxUserId(user_id),
-                        place_id: place_id.and_then(|id| Some(PlaceId(id))),
+                        place_id: place_id.map(PlaceId),
                         active,
                         duration,
                         private_reason,
@@ -222,12 +219,7 @@ impl UserRestriction {
                 let client = Client::new(&api_key);
                 let user_restriction_client = client.user_restriction(UniverseId(universe_id));
                 let res = user_restriction_client
-                    .list_user_restrictions(
-                        place_id.and_then(|id| Some(PlaceId(id))),
-                        page_size,
-                        filter,
-                        token,
-                    )
+                    .list_user_restrictions(place_id.map(PlaceId), page_size, filter, token)
                     .await;
                 match res {
                     Ok(info) => {
@@ -254,12 +246,7 @@ impl UserRestriction {
                 let client = Client::new(&api_key);
                 let user_restriction_client = client.user_restriction(UniverseId(universe_id));
                 let res = user_restriction_client
-                    .list_user_restriction_logs(
-                        place_id.and_then(|id| Some(PlaceId(id))),
-                        page_size,
-                        filter,
-                        token,
-                    )
+                    .list_user_restriction_logs(place_id.map(PlaceId), page_size, filter, token)
                     .await;
                 match res {
                     Ok(info) => {
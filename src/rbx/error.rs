@@ -1,5 +1,8 @@
 //! Error handling.
-use crate::rbx::v1::ds_error::DataStoreErrorResponse;
+use crate::rbx::{
+    json_schema::SchemaViolation,
+    v1::ds_error::{DataStoreErrorCode, DataStoreErrorResponse},
+};
 
 /// `rbxcloud` error.
 #[derive(Debug)]
@@ -25,15 +28,73 @@ pub enum Error {
     /// A DataStore error occurred.
     DataStoreError(DataStoreErrorResponse),
 
+    /// A DataStore request was rejected before being sent, because it exceeds
+    /// one of the documented DataStore limits. The code is the one the
+    /// server would have responded with.
+    DataStorePreflightError {
+        code: DataStoreErrorCode,
+        msg: String,
+    },
+
     /// Failed to parse a float.
     ParseFloatError(std::num::ParseFloatError),
 
     /// Endpoint error.
     EndpointError(String),
+
+    /// Failed to parse or evaluate an expression.
+    ExpressionError(String),
+
+    /// A JSON Schema could not be parsed.
+    InvalidSchema(String),
+
+    /// A value does not match its JSON Schema.
+    SchemaValidationError(Vec<SchemaViolation>),
+
+    /// A time is missing or cannot be parsed.
+    InvalidTime(String),
+
+    /// Two sources cannot be compared as requested.
+    DiffError(String),
+
+    /// No tracked snapshot matches the request.
+    SnapshotNotFound(String),
+
+    /// A DataStore value is not a ProfileService/ProfileStore profile.
+    InvalidProfile(String),
+
+    /// A profile is loaded by a game server and cannot be edited.
+    ProfileSessionLocked(String),
+
+    /// An OrderedDataStore filter or order is invalid.
+    OrderedFilterError(String),
+
+    /// A leaderboard rollover cannot be started or resumed.
+    RolloverError(String),
+
+    /// A MemoryStore request was rejected before being sent, e.g. because of
+    /// an invalid TTL.
+    MemoryStoreError(String),
+
+    /// A message was rejected before being sent, because it exceeds one of
+    /// the MessagingService limits.
+    MessagingError(String),
 }
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Returns `true` if this is a DataStore error (from the server or from a
+    /// preflight check) with the given code.
+    pub fn has_datastore_code(&self, code: DataStoreErrorCode) -> bool {
+        match self {
+            Self::DataStoreError(res) => res.has_code(code),
+            Self::DataStorePreflightError { code: c, .. } => *c == code,
+            _ => false,
+        }
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
         Self::ReqwestError(e)
@@ -68,8 +129,30 @@ impl std::fmt::Display for Error {
             Self::IOError(e) => write!(f, "{e:?}"),
             Self::SerdeJsonError(e) => write!(f, "{e:?}"),
             Self::DataStoreError(e) => write!(f, "{e:?}"),
+            Self::DataStorePreflightError { code, msg } => write!(f, "[{code:?}] - {msg}"),
             Self::ParseFloatError(e) => write!(f, "{e:?}"),
             Self::EndpointError(s) => write!(f, "endpoint error: {s}"),
+            Self::ExpressionError(s) => write!(f, "expression error: {s}"),
+            Self::InvalidSchema(s) => write!(f, "invalid schema: {s}"),
+            Self::SchemaValidationError(violations) => {
+                write!(f, "value does not match schema: ")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{violation}")?;
+                }
+                Ok(())
+            }
+            Self::InvalidTime(s) => write!(f, "invalid time: {s}"),
+            Self::DiffError(s) => write!(f, "diff error: {s}"),
+            Self::SnapshotNotFound(s) => write!(f, "{s}"),
+            Self::InvalidProfile(s) => write!(f, "invalid profile: {s}"),
+            Self::ProfileSessionLocked(s) => write!(f, "profile is session-locked by {s}"),
+            Self::OrderedFilterError(s) => write!(f, "invalid ordered datastore filter: {s}"),
+            Self::RolloverError(s) => write!(f, "rollover error: {s}"),
+            Self::MemoryStoreError(s) => write!(f, "memory store error: {s}"),
+            Self::MessagingError(s) => write!(f, "messaging error: {s}"),
         }
     }
 }
@@ -0,0 +1,872 @@
+//! A small jq-style expression language for reshaping JSON values.
+//!
+//! Only a subset of jq is supported:
+//!
+//! - Paths: `.`, `.coins`, `.stats.level`, `.items[0]`, `."odd key"`, `.["odd key"]`
+//! - Literals: `1`, `-2.5`, `"text"`, `true`, `false`, `null`
+//! - Construction: `{coins: .gold, level}`, `[.a, .b]`
+//! - Assignment and deletion: `.version = 2`, `del(.legacy)` (an array
+//!   index can be set up to one past the last element, which appends)
+//! - Alternative: `.coins // 0` (binds looser than `=`, as in jq)
+//! - Comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=` (values of different types
+//!   are ordered as in jq: null, false, true, numbers, strings, arrays, objects)
+//! - Boolean logic: `.vip and .coins > 100`, `.a or .b`, `.banned | not`
+//! - Pipes: `del(.legacy) | .version = 2`
+//!
+//! ```rust
+//! use rbxcloud::rbx::expr::Expression;
+//! use serde_json::json;
+//!
+//! let expr = Expression::parse(".coins = (.gold // 0) | del(.gold)").unwrap();
+//! let out = expr.evaluate(&json!({"gold": 5})).unwrap();
+//! assert_eq!(out, json!({"coins": 5}));
+//!
+//! let expr = Expression::parse(".coins > 1e9 and (.banned | not)").unwrap();
+//! assert!(expr.matches(&json!({"coins": 2e9, "banned": false})).unwrap());
+//! ```
+use std::{cmp::Ordering, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::rbx::error::Error;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Ident(String),
+    Str(String),
+    Number(serde_json::Number),
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Colon,
+    Comma,
+    Pipe,
+    Alt,
+    Assign,
+    Cmp(CmpOp),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(i64),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Path(Vec<PathSegment>),
+    Literal(Value),
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>),
+    Pipe(Box<Node>, Box<Node>),
+    Alternative(Box<Node>, Box<Node>),
+    Assign(Vec<PathSegment>, Box<Node>),
+    Delete(Vec<PathSegment>),
+    Compare(CmpOp, Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not,
+}
+
+/// A parsed jq-style expression.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+fn expr_err<T>(msg: impl Into<String>) -> Result<T, Error> {
+    Err(Error::ExpressionError(msg.into()))
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, Error> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '.' => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            '{' => {
+                tokens.push(Token::LBrace);
+                i += 1;
+            }
+            '}' => {
+                tokens.push(Token::RBrace);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ':' => {
+                tokens.push(Token::Colon);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '|' => {
+                tokens.push(Token::Pipe);
+                i += 1;
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Cmp(CmpOp::Eq));
+                i += 2;
+            }
+            '=' => {
+                tokens.push(Token::Assign);
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Cmp(CmpOp::Ne));
+                i += 2;
+            }
+            '<' | '>' => {
+                let or_equal = chars.get(i + 1) == Some(&'=');
+                tokens.push(Token::Cmp(match (c, or_equal) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    ('>', false) => CmpOp::Gt,
+                    _ => CmpOp::Ge,
+                }));
+                i += if or_equal { 2 } else { 1 };
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                tokens.push(Token::Alt);
+                i += 2;
+            }
+            '"' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return expr_err("unterminated string literal");
+                }
+                i += 1;
+                let literal: String = chars[start..i].iter().collect();
+                tokens.push(Token::Str(serde_json::from_str(&literal)?));
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || matches!(chars[i], '.' | 'e' | 'E')
+                        || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E')))
+                {
+                    i += 1;
+                }
+                let literal: String = chars[start..i].iter().collect();
+                match serde_json::from_str::<serde_json::Number>(&literal) {
+                    Ok(n) => tokens.push(Token::Number(n)),
+                    Err(_) => return expr_err(format!("invalid number '{literal}'")),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            c => return expr_err(format!("unexpected character '{c}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), Error> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            Some(t) => expr_err(format!("expected {token:?}, found {t:?}")),
+            None => expr_err(format!("expected {token:?}, found end of expression")),
+        }
+    }
+
+    fn parse_pipe(&mut self) -> Result<Node, Error> {
+        let mut left = self.parse_alternative()?;
+        while self.peek() == Some(&Token::Pipe) {
+            self.next();
+            let right = self.parse_alternative()?;
+            left = Node::Pipe(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_alternative(&mut self) -> Result<Node, Error> {
+        let mut left = self.parse_assign()?;
+        while self.peek() == Some(&Token::Alt) {
+            self.next();
+            let right = self.parse_assign()?;
+            left = Node::Alternative(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_assign(&mut self) -> Result<Node, Error> {
+        let left = self.parse_or()?;
+        if self.peek() != Some(&Token::Assign) {
+            return Ok(left);
+        }
+        self.next();
+        let Node::Path(path) = left else {
+            return expr_err("left side of '=' must be a path");
+        };
+        // As in jq, `//` binds looser than `=`: `.a = .b // 0` is
+        // `(.a = .b) // 0`.
+        let right = self.parse_or()?;
+        if self.peek() == Some(&Token::Assign) {
+            return expr_err("assignments cannot be chained");
+        }
+        Ok(Node::Assign(path, Box::new(right)))
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword)
+    }
+
+    fn parse_or(&mut self) -> Result<Node, Error> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.next();
+            let right = self.parse_and()?;
+            left = Node::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, Error> {
+        let mut left = self.parse_comparison()?;
+        while self.peek_keyword("and") {
+            self.next();
+            let right = self.parse_comparison()?;
+            left = Node::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Node, Error> {
+        let left = self.parse_primary()?;
+        let Some(Token::Cmp(op)) = self.peek().cloned() else {
+            return Ok(left);
+        };
+        self.next();
+        let right = self.parse_primary()?;
+        if let Some(Token::Cmp(_)) = self.peek() {
+            return expr_err("comparisons cannot be chained; use 'and'");
+        }
+        Ok(Node::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Node, Error> {
+        match self.next() {
+            Some(Token::Dot) => Ok(Node::Path(self.parse_path_segments(true)?)),
+            Some(Token::Number(n)) => Ok(Node::Literal(Value::Number(n))),
+            Some(Token::Str(s)) => Ok(Node::Literal(Value::String(s))),
+            Some(Token::LParen) => {
+                let inner = self.parse_pipe()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::LBracket) => {
+                let mut items = vec![];
+                if self.peek() != Some(&Token::RBracket) {
+                    loop {
+                        items.push(self.parse_alternative()?);
+                        if self.peek() == Some(&Token::Comma) {
+                            self.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RBracket)?;
+                Ok(Node::Array(items))
+            }
+            Some(Token::LBrace) => self.parse_object(),
+            Some(Token::Ident(ident)) => self.parse_ident(ident),
+            Some(t) => expr_err(format!("unexpected token {t:?}")),
+            None => expr_err("unexpected end of expression"),
+        }
+    }
+
+    fn parse_ident(&mut self, ident: String) -> Result<Node, Error> {
+        match ident.as_str() {
+            "true" => Ok(Node::Literal(Value::Bool(true))),
+            "false" => Ok(Node::Literal(Value::Bool(false))),
+            "null" => Ok(Node::Literal(Value::Null)),
+            "not" => Ok(Node::Not),
+            "del" => {
+                self.expect(Token::LParen)?;
+                self.expect(Token::Dot)?;
+                let path = self.parse_path_segments(true)?;
+                self.expect(Token::RParen)?;
+                if path.is_empty() {
+                    return expr_err("del() requires a non-empty path");
+                }
+                Ok(Node::Delete(path))
+            }
+            _ => expr_err(format!("unknown identifier '{ident}'")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node, Error> {
+        let mut fields = vec![];
+        if self.peek() != Some(&Token::RBrace) {
+            loop {
+                let key = match self.next() {
+                    Some(Token::Ident(k)) | Some(Token::Str(k)) => k,
+                    Some(t) => return expr_err(format!("invalid object key {t:?}")),
+                    None => return expr_err("unexpected end of object"),
+                };
+                let value = if self.peek() == Some(&Token::Colon) {
+                    self.next();
+                    self.parse_alternative()?
+                } else {
+                    Node::Path(vec![PathSegment::Key(key.clone())])
+                };
+                fields.push((key, value));
+                if self.peek() == Some(&Token::Comma) {
+                    self.next();
+                } else {
+                    break;
+                }
+            }
+        }
+        self.expect(Token::RBrace)?;
+        Ok(Node::Object(fields))
+    }
+
+    /// Parse the segments following a leading `.`.
+    fn parse_path_segments(&mut self, after_dot: bool) -> Result<Vec<PathSegment>, Error> {
+        let mut segments = vec![];
+        let mut expect_key = after_dot;
+        loop {
+            match self.peek().cloned() {
+                Some(Token::Ident(k)) if expect_key => {
+                    self.next();
+                    segments.push(PathSegment::Key(k));
+                }
+                Some(Token::Str(k)) if expect_key => {
+                    self.next();
+                    segments.push(PathSegment::Key(k));
+                }
+                Some(Token::LBracket) => {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Number(n)) => match n.as_i64() {
+                            Some(index) => segments.push(PathSegment::Index(index)),
+                            None => return expr_err(format!("invalid array index {n}")),
+                        },
+                        Some(Token::Str(k)) => segments.push(PathSegment::Key(k)),
+                        Some(t) => return expr_err(format!("invalid index {t:?}")),
+                        None => return expr_err("unexpected end of index"),
+                    }
+                    self.expect(Token::RBracket)?;
+                }
+                Some(Token::Dot) => {
+                    self.next();
+                    expect_key = true;
+                    continue;
+                }
+                _ => break,
+            }
+            expect_key = false;
+        }
+        Ok(segments)
+    }
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let resolved = if index < 0 { len as i64 + index } else { index };
+    (resolved >= 0 && (resolved as usize) < len).then_some(resolved as usize)
+}
+
+fn get_path(input: &Value, path: &[PathSegment]) -> Result<Value, Error> {
+    let mut current = input;
+    for segment in path {
+        current = match (segment, current) {
+            (_, Value::Null) => return Ok(Value::Null),
+            (PathSegment::Key(k), Value::Object(map)) => match map.get(k) {
+                Some(v) => v,
+                None => return Ok(Value::Null),
+            },
+            (PathSegment::Index(i), Value::Array(arr)) => match resolve_index(*i, arr.len()) {
+                Some(i) => &arr[i],
+                None => return Ok(Value::Null),
+            },
+            (PathSegment::Key(k), other) => {
+                return expr_err(format!("cannot index {} with \"{k}\"", type_name(other)))
+            }
+            (PathSegment::Index(i), other) => {
+                return expr_err(format!("cannot index {} with {i}", type_name(other)))
+            }
+        };
+    }
+    Ok(current.clone())
+}
+
+fn set_path(target: &mut Value, path: &[PathSegment], value: Value) -> Result<(), Error> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    if target.is_null() {
+        *target = match segment {
+            PathSegment::Key(_) => Value::Object(Map::new()),
+            PathSegment::Index(_) => Value::Array(vec![]),
+        };
+    }
+    match (segment, target) {
+        (PathSegment::Key(k), Value::Object(map)) => {
+            set_path(map.entry(k.clone()).or_insert(Value::Null), rest, value)
+        }
+        (PathSegment::Index(i), Value::Array(arr)) => {
+            let index = if *i < 0 {
+                match resolve_index(*i, arr.len()) {
+                    Some(index) => index,
+                    None => return expr_err(format!("array index {i} out of bounds")),
+                }
+            } else {
+                *i as usize
+            };
+            if index > arr.len() {
+                return expr_err(format!(
+                    "array index {i} out of bounds (array has {} elements)",
+                    arr.len()
+                ));
+            }
+            if index == arr.len() {
+                arr.push(Value::Null);
+            }
+            set_path(&mut arr[index], rest, value)
+        }
+        (PathSegment::Key(k), other) => {
+            expr_err(format!("cannot index {} with \"{k}\"", type_name(other)))
+        }
+        (PathSegment::Index(i), other) => {
+            expr_err(format!("cannot index {} with {i}", type_name(other)))
+        }
+    }
+}
+
+fn delete_path(target: &mut Value, path: &[PathSegment]) -> Result<(), Error> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Ok(());
+    };
+    match (segment, target) {
+        (_, Value::Null) => Ok(()),
+        (PathSegment::Key(k), Value::Object(map)) => {
+            if rest.is_empty() {
+                map.remove(k);
+                Ok(())
+            } else {
+                match map.get_mut(k) {
+                    Some(child) => delete_path(child, rest),
+                    None => Ok(()),
+                }
+            }
+        }
+        (PathSegment::Index(i), Value::Array(arr)) => match resolve_index(*i, arr.len()) {
+            Some(index) if rest.is_empty() => {
+                arr.remove(index);
+                Ok(())
+            }
+            Some(index) => delete_path(&mut arr[index], rest),
+            None => Ok(()),
+        },
+        (PathSegment::Key(k), other) => {
+            expr_err(format!("cannot delete \"{k}\" from {}", type_name(other)))
+        }
+        (PathSegment::Index(i), other) => {
+            expr_err(format!("cannot delete {i} from {}", type_name(other)))
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Returns `false` for `null` and `false`, and `true` for every other value.
+pub fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn type_order(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    }
+}
+
+/// Total order over JSON values, following jq: values of different types are
+/// ordered by type, arrays are compared element by element, and objects by
+/// their sorted keys and then by their values.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let x = x.as_f64().unwrap_or(f64::NAN);
+            let y = y.as_f64().unwrap_or(f64::NAN);
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => {
+            for (x, y) in x.iter().zip(y) {
+                let ord = compare_values(x, y);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            x.len().cmp(&y.len())
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            let mut x_keys: Vec<&String> = x.keys().collect();
+            let mut y_keys: Vec<&String> = y.keys().collect();
+            x_keys.sort();
+            y_keys.sort();
+            x_keys.cmp(&y_keys).then_with(|| {
+                x_keys
+                    .iter()
+                    .map(|k| compare_values(&x[*k], &y[*k]))
+                    .find(|ord| *ord != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => type_order(a).cmp(&type_order(b)),
+    }
+}
+
+fn eval(node: &Node, input: &Value) -> Result<Value, Error> {
+    match node {
+        Node::Path(path) => get_path(input, path),
+        Node::Literal(value) => Ok(value.clone()),
+        Node::Object(fields) => {
+            let mut map = Map::new();
+            for (key, value) in fields {
+                map.insert(key.clone(), eval(value, input)?);
+            }
+            Ok(Value::Object(map))
+        }
+        Node::Array(items) => Ok(Value::Array(
+            items
+                .iter()
+                .map(|item| eval(item, input))
+                .collect::<Result<Vec<Value>, Error>>()?,
+        )),
+        Node::Pipe(left, right) => eval(right, &eval(left, input)?),
+        Node::Alternative(left, right) => {
+            let value = eval(left, input)?;
+            if is_truthy(&value) {
+                Ok(value)
+            } else {
+                eval(right, input)
+            }
+        }
+        Node::Assign(path, value) => {
+            let value = eval(value, input)?;
+            let mut output = input.clone();
+            set_path(&mut output, path, value)?;
+            Ok(output)
+        }
+        Node::Delete(path) => {
+            let mut output = input.clone();
+            delete_path(&mut output, path)?;
+            Ok(output)
+        }
+        Node::Compare(op, left, right) => {
+            let ord = compare_values(&eval(left, input)?, &eval(right, input)?);
+            Ok(Value::Bool(match op {
+                CmpOp::Eq => ord == Ordering::Equal,
+                CmpOp::Ne => ord != Ordering::Equal,
+                CmpOp::Lt => ord == Ordering::Less,
+                CmpOp::Le => ord != Ordering::Greater,
+                CmpOp::Gt => ord == Ordering::Greater,
+                CmpOp::Ge => ord != Ordering::Less,
+            }))
+        }
+        Node::And(left, right) => Ok(Value::Bool(
+            is_truthy(&eval(left, input)?) && is_truthy(&eval(right, input)?),
+        )),
+        Node::Or(left, right) => Ok(Value::Bool(
+            is_truthy(&eval(left, input)?) || is_truthy(&eval(right, input)?),
+        )),
+        Node::Not => Ok(Value::Bool(!is_truthy(input))),
+    }
+}
+
+impl Expression {
+    /// Parse an expression.
+    pub fn parse(source: &str) -> Result<Expression, Error> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+        if parser.tokens.is_empty() {
+            return expr_err("expression is empty");
+        }
+        let root = parser.parse_pipe()?;
+        if let Some(t) = parser.peek() {
+            return expr_err(format!("unexpected token {t:?}"));
+        }
+        Ok(Expression {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    /// Evaluate the expression against the given input value.
+    pub fn evaluate(&self, input: &Value) -> Result<Value, Error> {
+        eval(&self.root, input)
+    }
+
+    /// Evaluate the expression as a predicate: `true` unless the result is
+    /// `null` or `false`.
+    pub fn matches(&self, input: &Value) -> Result<bool, Error> {
+        Ok(is_truthy(&self.evaluate(input)?))
+    }
+
+    /// The source text the expression was parsed from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Expression::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval_str(expr: &str, input: Value) -> Result<Value, Error> {
+        Expression::parse(expr)?.evaluate(&input)
+    }
+
+    #[test]
+    fn tokenizes_numbers() {
+        for (literal, expected) in [
+            ("1", json!(1)),
+            ("-2.5", json!(-2.5)),
+            ("1e9", json!(1e9)),
+            ("1e-5", json!(1e-5)),
+            ("2.5E+3", json!(2500.0)),
+        ] {
+            assert_eq!(
+                eval_str(literal, Value::Null).unwrap(),
+                expected,
+                "{literal}"
+            );
+        }
+        assert!(tokenize("1e").is_err());
+        assert!(Expression::parse("1-2").is_err());
+    }
+
+    #[test]
+    fn tokenizes_operators_and_strings() {
+        let tokens = tokenize(r#".a // "x\"y" == <= != >="#).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Dot,
+                Token::Ident("a".to_string()),
+                Token::Alt,
+                Token::Str("x\"y".to_string()),
+                Token::Cmp(CmpOp::Eq),
+                Token::Cmp(CmpOp::Le),
+                Token::Cmp(CmpOp::Ne),
+                Token::Cmp(CmpOp::Ge),
+            ]
+        );
+        assert!(tokenize("\"open").is_err());
+        assert!(tokenize(".a $ 1").is_err());
+    }
+
+    #[test]
+    fn reads_paths() {
+        let input = json!({"a": {"b": [1, 2, 3]}, "odd key": true});
+        assert_eq!(eval_str(".a.b[0]", input.clone()).unwrap(), json!(1));
+        assert_eq!(eval_str(".a.b[-1]", input.clone()).unwrap(), json!(3));
+        assert_eq!(eval_str(".a.b[7]", input.clone()).unwrap(), Value::Null);
+        assert_eq!(
+            eval_str(".\"odd key\"", input.clone()).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            eval_str(".[\"odd key\"]", input.clone()).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            eval_str(".missing.deeper", input.clone()).unwrap(),
+            Value::Null
+        );
+        assert!(eval_str(".a.b.c", input).is_err());
+    }
+
+    #[test]
+    fn sets_and_deletes_paths() {
+        assert_eq!(
+            eval_str(".a.b = 1", json!({})).unwrap(),
+            json!({"a": {"b": 1}})
+        );
+        assert_eq!(
+            eval_str(".a[1] = 9", json!({"a": [0]})).unwrap(),
+            json!({"a": [0, 9]})
+        );
+        assert_eq!(eval_str(".[-1] = 9", json!([1, 2])).unwrap(), json!([1, 9]));
+        assert!(eval_str(".a[4000000000] = 1", json!({"a": []})).is_err());
+        assert!(eval_str(".[-3] = 1", json!([1, 2])).is_err());
+        assert_eq!(
+            eval_str("del(.a[0])", json!({"a": [1, 2]})).unwrap(),
+            json!({"a": [2]})
+        );
+        assert_eq!(
+            eval_str("del(.x.y)", json!({"a": 1})).unwrap(),
+            json!({"a": 1})
+        );
+    }
+
+    #[test]
+    fn follows_jq_precedence() {
+        // `//` binds looser than `=`.
+        assert_eq!(
+            eval_str(".a = .b // 0", json!({"b": 1})).unwrap(),
+            json!({"a": 1, "b": 1})
+        );
+        assert_eq!(
+            eval_str(".a = (.b // 0)", json!({})).unwrap(),
+            json!({"a": 0})
+        );
+        // `and` binds tighter than `or`, and comparisons tighter than both.
+        assert_eq!(
+            eval_str("true or false and false", Value::Null).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            eval_str(".a > 1 and .a < 3", json!({"a": 2})).unwrap(),
+            json!(true)
+        );
+        // Pipes bind loosest.
+        assert_eq!(eval_str(".a = 1 | .a", json!({})).unwrap(), json!(1));
+        assert_eq!(
+            eval_str(".a | not", json!({"a": null})).unwrap(),
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn compares_across_types() {
+        assert_eq!(eval_str("null < false", Value::Null).unwrap(), json!(true));
+        assert_eq!(eval_str("1 < \"a\"", Value::Null).unwrap(), json!(true));
+        assert_eq!(
+            eval_str("[1, 2] < [1, 3]", Value::Null).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            eval_str("{a: 1} == {a: 1}", Value::Null).unwrap(),
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn constructs_values() {
+        assert_eq!(
+            eval_str("{coins: .gold, level}", json!({"gold": 5, "level": 2})).unwrap(),
+            json!({"coins": 5, "level": 2})
+        );
+        assert_eq!(
+            eval_str("[.a, 1]", json!({"a": "x"})).unwrap(),
+            json!(["x", 1])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in [
+            "",
+            ".a ==",
+            "1 = 2",
+            ".a = .b = 1",
+            "1 < 2 < 3",
+            "del(.)",
+            "foo",
+            "(.a",
+            "{1: 2}",
+        ] {
+            assert!(Expression::parse(expr).is_err(), "{expr}");
+        }
+    }
+}
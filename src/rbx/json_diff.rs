@@ -0,0 +1,205 @@
+//! Structural diffs of JSON values.
+//!
+//! ```rust
+//! use rbxcloud::rbx::json_diff::{diff, JsonChangeKind};
+//! use serde_json::json;
+//!
+//! let changes = diff(&json!({"coins": 1, "old": true}), &json!({"coins": 2}));
+//! assert_eq!(changes.len(), 2);
+//! assert_eq!(changes[0].path, "/coins");
+//! assert_eq!(changes[0].kind, JsonChangeKind::Changed);
+//! ```
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JsonChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single difference between two JSON values.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonChange {
+    /// JSON pointer to the changed location (empty for the root value).
+    pub path: String,
+    pub kind: JsonChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+impl std::fmt::Display for JsonChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        let show = |v: &Option<Value>| v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        match self.kind {
+            JsonChangeKind::Added => write!(f, "+ {path}: {}", show(&self.new)),
+            JsonChangeKind::Removed => write!(f, "- {path}: {}", show(&self.old)),
+            JsonChangeKind::Changed => {
+                write!(f, "~ {path}: {} -> {}", show(&self.old), show(&self.new))
+            }
+        }
+    }
+}
+
+/// Escape a key for use as a JSON pointer segment (RFC 6901).
+pub fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn diff_into(path: &str, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{path}/{}", escape_pointer_segment(key));
+                match new_map.get(key) {
+                    Some(new_value) => diff_into(&child, old_value, new_value, changes),
+                    None => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        old: Some(old_value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    changes.push(JsonChange {
+                        path: format!("{path}/{}", escape_pointer_segment(key)),
+                        kind: JsonChangeKind::Added,
+                        old: None,
+                        new: Some(new_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(old_arr), Value::Array(new_arr)) => {
+            for i in 0..old_arr.len().max(new_arr.len()) {
+                let child = format!("{path}/{i}");
+                match (old_arr.get(i), new_arr.get(i)) {
+                    (Some(o), Some(n)) => diff_into(&child, o, n, changes),
+                    (Some(o), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        old: Some(o.clone()),
+                        new: None,
+                    }),
+                    (None, Some(n)) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Added,
+                        old: None,
+                        new: Some(n.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (o, n) if o != n => changes.push(JsonChange {
+            path: path.to_string(),
+            kind: JsonChangeKind::Changed,
+            old: Some(o.clone()),
+            new: Some(n.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Compute the structural differences going from `old` to `new`.
+///
+/// Objects are compared key by key and arrays index by index. An empty
+/// result means the values are equal.
+pub fn diff(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = vec![];
+    diff_into("", old, new, &mut changes);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn equal_values_have_no_changes() {
+        let value = json!({"a": [1, {"b": null}], "c": "x"});
+        assert!(diff(&value, &value).is_empty());
+    }
+
+    #[test]
+    fn diffs_objects_by_key() {
+        let changes = diff(&json!({"a": 1, "b": 2}), &json!({"a": 1, "c": 3}));
+        assert_eq!(
+            changes,
+            vec![
+                JsonChange {
+                    path: "/b".to_string(),
+                    kind: JsonChangeKind::Removed,
+                    old: Some(json!(2)),
+                    new: None,
+                },
+                JsonChange {
+                    path: "/c".to_string(),
+                    kind: JsonChangeKind::Added,
+                    old: None,
+                    new: Some(json!(3)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_arrays_by_index() {
+        let changes = diff(&json!([1, 2, 3]), &json!([1, 5]));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, "/1");
+        assert_eq!(changes[0].kind, JsonChangeKind::Changed);
+        assert_eq!(changes[1].path, "/2");
+        assert_eq!(changes[1].kind, JsonChangeKind::Removed);
+        assert_eq!(changes[1].old, Some(json!(3)));
+
+        let changes = diff(&json!([]), &json!([true]));
+        assert_eq!(changes[0].path, "/0");
+        assert_eq!(changes[0].kind, JsonChangeKind::Added);
+    }
+
+    #[test]
+    fn diffs_nested_values() {
+        let changes = diff(
+            &json!({"stats": {"level": 1, "xp": [1]}}),
+            &json!({"stats": {"level": 2, "xp": [1]}}),
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/stats/level");
+        assert_eq!(changes[0].old, Some(json!(1)));
+        assert_eq!(changes[0].new, Some(json!(2)));
+    }
+
+    #[test]
+    fn reports_type_changes_and_root_changes() {
+        let changes = diff(&json!({"a": [1]}), &json!({"a": {"0": 1}}));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/a");
+        assert_eq!(changes[0].kind, JsonChangeKind::Changed);
+
+        let changes = diff(&json!(1), &json!("1"));
+        assert_eq!(changes[0].path, "");
+        assert_eq!(changes[0].to_string(), "~ /: 1 -> \"1\"");
+    }
+
+    #[test]
+    fn escapes_pointer_segments() {
+        assert_eq!(escape_pointer_segment("a/b~c"), "a~1b~0c");
+        let changes = diff(&json!({}), &json!({"a/b": 1}));
+        assert_eq!(changes[0].path, "/a~1b");
+        assert_eq!(changes[0].to_string(), "+ /a~1b: 1");
+    }
+}
@@ -0,0 +1,544 @@
+//! Validation of JSON values against a JSON Schema.
+//!
+//! The following keywords are supported: `type`, `enum`, `const`,
+//! `properties`, `required`, `additionalProperties`, `minProperties`,
+//! `maxProperties`, `items`, `prefixItems`, `minItems`, `maxItems`,
+//! `uniqueItems`, `minimum`, `maximum`, `exclusiveMinimum`,
+//! `exclusiveMaximum`, `multipleOf`, `minLength`, `maxLength`, `allOf`,
+//! `anyOf`, `oneOf`, `not`, `if`/`then`/`else`, and local `$ref`s (e.g.
+//! `#/$defs/item`). Other keywords, such as `pattern` and `format`, are
+//! ignored.
+//!
+//! ```rust
+//! use rbxcloud::rbx::json_schema::JsonSchema;
+//! use serde_json::json;
+//!
+//! let schema = JsonSchema::new(json!({
+//!     "type": "object",
+//!     "properties": {"coins": {"type": "integer", "minimum": 0}},
+//!     "required": ["coins"]
+//! }))
+//! .unwrap();
+//!
+//! assert!(schema.validate(&json!({"coins": 10})).is_empty());
+//! let violations = schema.validate(&json!({"coins": -1}));
+//! assert_eq!(violations[0].path, "/coins");
+//! ```
+use std::{str::FromStr, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::rbx::{error::Error, json_diff::escape_pointer_segment};
+
+const MAX_DEPTH: usize = 64;
+
+/// A location in a value that does not match the schema.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value (empty for the root value).
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// A parsed JSON Schema.
+#[derive(Debug, Clone)]
+pub struct JsonSchema {
+    root: Arc<Value>,
+}
+
+impl FromStr for JsonSchema {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JsonSchema::new(serde_json::from_str(s)?)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if is_integer(n) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_integer(n: &serde_json::Number) -> bool {
+    n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match (expected, value) {
+        ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(n)) => is_integer(n),
+        _ => type_name(value) == expected,
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator<'_> {
+    fn fail(&mut self, path: &str, message: String) {
+        self.violations.push(SchemaViolation {
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    /// Validate `value` against `schema` in isolation, returning whether it matched.
+    fn passes(&self, schema: &Value, value: &Value, path: &str, depth: usize) -> bool {
+        let mut sub = Validator {
+            root: self.root,
+            violations: vec![],
+        };
+        sub.validate(schema, value, path, depth);
+        sub.violations.is_empty()
+    }
+
+    fn validate(&mut self, schema: &Value, value: &Value, path: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            self.fail(path, "schema is nested too deeply".to_string());
+            return;
+        }
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                self.fail(path, "no value is allowed here".to_string());
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => {
+                self.fail(path, "schema must be an object or a boolean".to_string());
+                return;
+            }
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => self.validate(target, value, path, depth + 1),
+                None => self.fail(path, format!("unresolved $ref \"{reference}\"")),
+            }
+        }
+
+        self.validate_generic(schema, value, path, depth);
+        match value {
+            Value::Object(object) => self.validate_object(schema, object, path, depth),
+            Value::Array(array) => self.validate_array(schema, array, path, depth),
+            Value::Number(n) => {
+                if let Some(n) = n.as_f64() {
+                    self.validate_number(schema, n, path)
+                }
+            }
+            Value::String(s) => self.validate_string(schema, s, path),
+            _ => {}
+        }
+    }
+
+    fn validate_generic(
+        &mut self,
+        schema: &Map<String, Value>,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) {
+        match schema.get("type") {
+            Some(Value::String(expected)) if !matches_type(value, expected) => self.fail(
+                path,
+                format!("expected {expected}, found {}", type_name(value)),
+            ),
+            Some(Value::Array(expected)) => {
+                let expected: Vec<&str> = expected.iter().filter_map(Value::as_str).collect();
+                if !expected.iter().any(|t| matches_type(value, t)) {
+                    self.fail(
+                        path,
+                        format!(
+                            "expected one of {}, found {}",
+                            expected.join(", "),
+                            type_name(value)
+                        ),
+                    );
+                }
+            }
+            _ => {}
+        }
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                self.fail(path, "value is not one of the allowed values".to_string());
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                self.fail(path, format!("expected {expected}"));
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for sub in schemas {
+                self.validate(sub, value, path, depth + 1);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas
+                .iter()
+                .any(|sub| self.passes(sub, value, path, depth + 1))
+            {
+                self.fail(path, "value does not match any schema of anyOf".to_string());
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matched = schemas
+                .iter()
+                .filter(|sub| self.passes(sub, value, path, depth + 1))
+                .count();
+            if matched != 1 {
+                self.fail(
+                    path,
+                    format!("value matches {matched} schemas of oneOf instead of exactly 1"),
+                );
+            }
+        }
+        if let Some(sub) = schema.get("not") {
+            if self.passes(sub, value, path, depth + 1) {
+                self.fail(path, "value must not match the schema of not".to_string());
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.passes(condition, value, path, depth + 1) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.validate(branch, value, path, depth + 1);
+            }
+        }
+    }
+
+    fn validate_object(
+        &mut self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    self.fail(path, format!("missing required property \"{name}\""));
+                }
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (object.len() as u64) < min {
+                self.fail(path, format!("expected at least {min} properties"));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if object.len() as u64 > max {
+                self.fail(path, format!("expected at most {max} properties"));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (name, child) in object {
+            let child_path = format!("{path}/{}", escape_pointer_segment(name));
+            match properties.and_then(|p| p.get(name)) {
+                Some(sub) => self.validate(sub, child, &child_path, depth + 1),
+                None => match additional {
+                    Some(Value::Bool(false)) => {
+                        self.fail(&child_path, "property is not allowed".to_string())
+                    }
+                    Some(sub) => self.validate(sub, child, &child_path, depth + 1),
+                    None => {}
+                },
+            }
+        }
+    }
+
+    fn validate_array(
+        &mut self,
+        schema: &Map<String, Value>,
+        array: &[Value],
+        path: &str,
+        depth: usize,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (array.len() as u64) < min {
+                self.fail(path, format!("expected at least {min} items"));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if array.len() as u64 > max {
+                self.fail(path, format!("expected at most {max} items"));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (i, item) in array.iter().enumerate() {
+                if array[..i].contains(item) {
+                    self.fail(&format!("{path}/{i}"), "item is not unique".to_string());
+                }
+            }
+        }
+
+        // `prefixItems` (or the older array form of `items`) validates items
+        // by position, and `items` validates the remaining ones.
+        let (prefix, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), rest) => (prefix.as_slice(), rest),
+            (_, Some(Value::Array(prefix))) => (prefix.as_slice(), schema.get("additionalItems")),
+            (_, rest) => (&[][..], rest),
+        };
+        for (i, item) in array.iter().enumerate() {
+            let sub = prefix.get(i).or(rest);
+            if let Some(sub) = sub {
+                self.validate(sub, item, &format!("{path}/{i}"), depth + 1);
+            }
+        }
+    }
+
+    fn validate_number(&mut self, schema: &Map<String, Value>, n: f64, path: &str) {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                self.fail(path, format!("{n} is less than the minimum of {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                self.fail(path, format!("{n} is greater than the maximum of {max}"));
+            }
+        }
+        if let Some(min) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+            if n <= min {
+                self.fail(path, format!("{n} must be greater than {min}"));
+            }
+        }
+        if let Some(max) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+            if n >= max {
+                self.fail(path, format!("{n} must be less than {max}"));
+            }
+        }
+        if let Some(step) = schema.get("multipleOf").and_then(Value::as_f64) {
+            if step > 0.0 && ((n / step).round() * step - n).abs() > f64::EPSILON * n.abs() {
+                self.fail(path, format!("{n} is not a multiple of {step}"));
+            }
+        }
+    }
+
+    fn validate_string(&mut self, schema: &Map<String, Value>, s: &str, path: &str) {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if len < min {
+                self.fail(path, format!("expected at least {min} characters"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if len > max {
+                self.fail(path, format!("expected at most {max} characters"));
+            }
+        }
+    }
+}
+
+impl JsonSchema {
+    /// Create a schema from its JSON representation, which must be an object
+    /// or a boolean.
+    pub fn new(schema: Value) -> Result<Self, Error> {
+        match schema {
+            Value::Object(_) | Value::Bool(_) => Ok(Self {
+                root: Arc::new(schema),
+            }),
+            _ => Err(Error::InvalidSchema(
+                "schema must be an object or a boolean".to_string(),
+            )),
+        }
+    }
+
+    /// Read a schema from a JSON file.
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| Error::FileLoadError(format!("{path}: {err}")))?;
+        contents.parse()
+    }
+
+    /// The JSON representation of the schema.
+    pub fn as_value(&self) -> &Value {
+        &self.root
+    }
+
+    /// Validate a value, returning every violation found. An empty result
+    /// means the value matches the schema.
+    pub fn validate(&self, value: &Value) -> Vec<SchemaViolation> {
+        let mut validator = Validator {
+            root: &self.root,
+            violations: vec![],
+        };
+        validator.validate(&self.root, value, "", 0);
+        validator.violations
+    }
+
+    /// Validate a value, returning `Error::SchemaValidationError` if it does
+    /// not match the schema.
+    pub fn check(&self, value: &Value) -> Result<(), Error> {
+        let violations = self.validate(value);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::SchemaValidationError(violations))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(schema: Value, value: Value) -> Vec<String> {
+        JsonSchema::new(schema)
+            .unwrap()
+            .validate(&value)
+            .into_iter()
+            .map(|v| v.path)
+            .collect()
+    }
+
+    #[test]
+    fn checks_types() {
+        let schema = json!({"type": "integer"});
+        assert!(paths(schema.clone(), json!(3)).is_empty());
+        assert!(paths(schema.clone(), json!(3.0)).is_empty());
+        assert_eq!(paths(schema.clone(), json!(3.5)), vec![""]);
+        assert_eq!(paths(schema, json!("3")), vec![""]);
+
+        let schema = json!({"type": ["string", "null"]});
+        assert!(paths(schema.clone(), json!(null)).is_empty());
+        assert!(paths(schema.clone(), json!("x")).is_empty());
+        assert_eq!(paths(schema, json!(false)), vec![""]);
+    }
+
+    #[test]
+    fn checks_required_and_additional_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"coins": {"type": "integer"}},
+            "required": ["coins", "level"],
+            "additionalProperties": false
+        });
+        // "level" is required but not listed in `properties`.
+        assert_eq!(
+            paths(schema.clone(), json!({"coins": 1, "level": 2})),
+            vec!["/level"]
+        );
+        let violations = JsonSchema::new(schema)
+            .unwrap()
+            .validate(&json!({"extra": true}));
+        assert_eq!(violations.len(), 3);
+        assert!(violations.iter().any(|v| v.message.contains("coins")));
+        assert!(violations.iter().any(|v| v.message.contains("level")));
+        assert!(violations.iter().any(|v| v.path == "/extra"));
+    }
+
+    #[test]
+    fn checks_enum_and_const() {
+        let schema = json!({"enum": ["red", "green", 1]});
+        assert!(paths(schema.clone(), json!("green")).is_empty());
+        assert!(paths(schema.clone(), json!(1)).is_empty());
+        assert_eq!(paths(schema, json!("blue")), vec![""]);
+        assert_eq!(paths(json!({"const": {"a": 1}}), json!({"a": 2})), vec![""]);
+    }
+
+    #[test]
+    fn checks_numeric_and_length_bounds() {
+        let schema = json!({"minimum": 0, "exclusiveMaximum": 10, "multipleOf": 2});
+        assert!(paths(schema.clone(), json!(0)).is_empty());
+        assert!(paths(schema.clone(), json!(8)).is_empty());
+        assert_eq!(paths(schema.clone(), json!(-2)), vec![""]);
+        assert_eq!(paths(schema.clone(), json!(10)), vec![""]);
+        assert_eq!(paths(schema, json!(3)), vec![""]);
+
+        let schema = json!({"minLength": 2, "maxLength": 3});
+        assert!(paths(schema.clone(), json!("ab")).is_empty());
+        assert_eq!(paths(schema.clone(), json!("a")), vec![""]);
+        assert_eq!(paths(schema, json!("abcd")), vec![""]);
+
+        let schema = json!({"minItems": 1, "maxItems": 2, "uniqueItems": true});
+        assert!(paths(schema.clone(), json!([1, 2])).is_empty());
+        assert_eq!(paths(schema.clone(), json!([])), vec![""]);
+        assert_eq!(paths(schema.clone(), json!([1, 2, 3])), vec![""]);
+        assert_eq!(paths(schema, json!([1, 1])), vec!["/1"]);
+    }
+
+    #[test]
+    fn reports_nested_paths() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "inventory": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"count": {"type": "integer", "minimum": 1}},
+                        "required": ["count"]
+                    }
+                },
+                "a/b": {"type": "string"}
+            }
+        });
+        let value = json!({
+            "inventory": [{"count": 1}, {"count": 0}, {}],
+            "a/b": 1
+        });
+        let mut found = paths(schema, value);
+        found.sort();
+        assert_eq!(found, vec!["/a~1b", "/inventory/1/count", "/inventory/2"]);
+    }
+
+    #[test]
+    fn resolves_refs_and_combinators() {
+        let schema = json!({
+            "$defs": {"positive": {"type": "number", "exclusiveMinimum": 0}},
+            "properties": {
+                "a": {"$ref": "#/$defs/positive"},
+                "b": {"anyOf": [{"type": "string"}, {"type": "null"}]},
+                "c": {"oneOf": [{"minimum": 0}, {"maximum": 10}]},
+                "d": {"not": {"type": "boolean"}}
+            }
+        });
+        assert!(paths(schema.clone(), json!({"a": 1, "b": null, "c": -5, "d": 0})).is_empty());
+        let mut found = paths(schema, json!({"a": 0, "b": 1, "c": 5, "d": true}));
+        found.sort();
+        assert_eq!(found, vec!["/a", "/b", "/c", "/d"]);
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(JsonSchema::new(json!(1)).is_err());
+        assert!(JsonSchema::new(json!(true)).is_ok());
+        assert!(!JsonSchema::new(json!(false))
+            .unwrap()
+            .validate(&json!(1))
+            .is_empty());
+    }
+}
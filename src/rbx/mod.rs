@@ -2,6 +2,9 @@
 //!
 //! Most usage should go through the `RbxCloud` struct.
 pub mod error;
+pub mod expr;
+pub mod json_diff;
+pub mod json_schema;
 pub mod types;
 pub(crate) mod util;
 pub mod v1;
@@ -1,5 +1,12 @@
+use std::{future::Future, sync::Arc};
+
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use md5::{Digest, Md5};
+use tokio::{
+    sync::Semaphore,
+    task::JoinSet,
+    time::{sleep_until, Duration, Instant},
+};
 
 pub type QueryString = Vec<(&'static str, String)>;
 
@@ -9,3 +16,90 @@ pub fn get_checksum_base64(data: &String) -> String {
     md5_hash.update(data.as_bytes());
     STANDARD.encode(md5_hash.finalize())
 }
+
+/// Match `text` against a glob pattern, where `*` matches any sequence of
+/// characters and `?` matches a single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Run `f` over every item with at most `concurrency` futures in flight.
+///
+/// Results are returned in the same order as the input items.
+pub async fn map_concurrent<T, R, F, Fut>(items: Vec<T>, concurrency: usize, f: F) -> Vec<R>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut set = JoinSet::new();
+    let count = items.len();
+    for (i, item) in items.into_iter().enumerate() {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore should not be closed");
+        let fut = f(item);
+        set.spawn(async move {
+            let res = fut.await;
+            drop(permit);
+            (i, res)
+        });
+    }
+    let mut results: Vec<Option<R>> = (0..count).map(|_| None).collect();
+    while let Some(joined) = set.join_next().await {
+        let (i, res) = joined.expect("concurrent task panicked");
+        results[i] = Some(res);
+    }
+    results.into_iter().flatten().collect()
+}
+
+/// Spaces out requests to stay under a per-minute budget.
+pub(crate) struct Throttle {
+    interval: Duration,
+    next: Instant,
+}
+
+impl Throttle {
+    pub(crate) fn new(max_requests_per_minute: u32) -> Self {
+        let interval = match max_requests_per_minute {
+            0 => Duration::ZERO,
+            n => Duration::from_secs(60) / n,
+        };
+        Self {
+            interval,
+            next: Instant::now(),
+        }
+    }
+
+    pub(crate) async fn wait(&mut self) {
+        sleep_until(self.next).await;
+        self.next = Instant::now() + self.interval;
+    }
+}
@@ -11,7 +11,10 @@ use crate::rbx::{
     util::{get_checksum_base64, QueryString},
 };
 
-use crate::rbx::v1::{ds_error::DataStoreErrorResponse, ReturnLimit, RobloxUserId, UniverseId};
+use crate::rbx::v1::{
+    ds_error::{DataStoreErrorCode, DataStoreErrorResponse},
+    ReturnLimit, RobloxUserId, UniverseId,
+};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -68,6 +71,41 @@ pub struct GetEntryParams {
     pub key: String,
 }
 
+/// User-defined attributes of an entry, sent and received as a JSON object in
+/// the `roblox-entry-attributes` header.
+pub type EntryAttributes = serde_json::Map<String, serde_json::Value>;
+
+/// Convert any serializable value into entry attributes. The value must
+/// serialize into a JSON object.
+pub fn entry_attributes<A: Serialize>(attributes: &A) -> Result<EntryAttributes, Error> {
+    match serde_json::to_value(attributes)? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err(Error::DataStorePreflightError {
+            code: DataStoreErrorCode::InvalidAttributes,
+            msg: "attributes must be a JSON object".to_string(),
+        }),
+    }
+}
+
+/// Metadata returned alongside an entry value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryMetadata {
+    pub version: Option<String>,
+    pub created_time: Option<String>,
+    pub version_created_time: Option<String>,
+    pub user_ids: Vec<RobloxUserId>,
+    pub attributes: Option<EntryAttributes>,
+    pub content_md5: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetEntryWithMetadataResponse {
+    pub data: String,
+    pub metadata: EntryMetadata,
+}
+
 pub struct SetEntryParams {
     pub api_key: String,
     pub universe_id: UniverseId,
@@ -77,7 +115,7 @@ pub struct SetEntryParams {
     pub match_version: Option<String>,
     pub exclusive_create: Option<bool>,
     pub roblox_entry_user_ids: Option<Vec<RobloxUserId>>,
-    pub roblox_entry_attributes: Option<String>,
+    pub roblox_entry_attributes: Option<EntryAttributes>,
     pub data: String,
 }
 
@@ -98,7 +136,7 @@ pub struct IncrementEntryParams {
     pub scope: Option<String>,
     pub key: String,
     pub roblox_entry_user_ids: Option<Vec<RobloxUserId>>,
-    pub roblox_entry_attributes: Option<String>,
+    pub roblox_entry_attributes: Option<EntryAttributes>,
     pub increment_by: f64,
 }
 
@@ -149,6 +187,123 @@ pub struct GetEntryVersionParams {
     pub version_id: String,
 }
 
+/// Returns `true` if the error is a DataStore `EntryNotFound` error.
+pub fn is_entry_not_found(err: &Error) -> bool {
+    err.has_datastore_code(DataStoreErrorCode::EntryNotFound)
+}
+
+/// Maximum size of an entry value, in bytes.
+pub const MAX_VALUE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Maximum length of a DataStore name, in bytes.
+pub const MAX_NAME_LENGTH: usize = 50;
+
+/// Maximum length of a scope, in bytes.
+pub const MAX_SCOPE_LENGTH: usize = 50;
+
+/// Maximum length of an entry key, in bytes.
+pub const MAX_KEY_LENGTH: usize = 50;
+
+/// Maximum size of the JSON-encoded entry attributes, in bytes.
+pub const MAX_ATTRIBUTES_SIZE: usize = 300;
+
+/// Maximum number of user IDs associated with an entry.
+pub const MAX_USER_IDS: usize = 4;
+
+fn preflight_err(code: DataStoreErrorCode, msg: String) -> Error {
+    Error::DataStorePreflightError { code, msg }
+}
+
+fn check_identifier(
+    kind: &str,
+    value: &str,
+    max: usize,
+    code: DataStoreErrorCode,
+) -> Result<(), Error> {
+    if value.is_empty() {
+        return Err(preflight_err(code, format!("{kind} cannot be empty")));
+    }
+    if value.len() > max {
+        return Err(preflight_err(
+            code,
+            format!("{kind} is {} bytes (max {max})", value.len()),
+        ));
+    }
+    Ok(())
+}
+
+/// Check a DataStore name, scope, and key against the documented limits.
+pub fn preflight_key(name: &str, scope: Option<&str>, key: &str) -> Result<(), Error> {
+    check_identifier(
+        "datastore name",
+        name,
+        MAX_NAME_LENGTH,
+        DataStoreErrorCode::InvalidDataStoreName,
+    )?;
+    if let Some(scope) = scope {
+        check_identifier(
+            "scope",
+            scope,
+            MAX_SCOPE_LENGTH,
+            DataStoreErrorCode::InvalidDataStoreScope,
+        )?;
+        if scope.contains('/') {
+            return Err(preflight_err(
+                DataStoreErrorCode::InvalidDataStoreScope,
+                "scope cannot contain '/'".to_string(),
+            ));
+        }
+    }
+    check_identifier(
+        "entry key",
+        key,
+        MAX_KEY_LENGTH,
+        DataStoreErrorCode::InvalidEntryKey,
+    )
+}
+
+/// Check entry user IDs and attributes against the documented limits.
+pub fn preflight_metadata(
+    user_ids: Option<&[RobloxUserId]>,
+    attributes: Option<&EntryAttributes>,
+) -> Result<(), Error> {
+    if let Some(user_ids) = user_ids {
+        if user_ids.len() > MAX_USER_IDS {
+            return Err(preflight_err(
+                DataStoreErrorCode::InvalidUserIds,
+                format!("{} user IDs given (max {MAX_USER_IDS})", user_ids.len()),
+            ));
+        }
+    }
+    if let Some(attributes) = attributes {
+        let size = attributes_header(Some(attributes)).len();
+        if size > MAX_ATTRIBUTES_SIZE {
+            return Err(preflight_err(
+                DataStoreErrorCode::InvalidAttributes,
+                format!("attributes are {size} bytes (max {MAX_ATTRIBUTES_SIZE})"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check an entry value against the documented limits.
+pub fn preflight_value(data: &str) -> Result<(), Error> {
+    if data.len() > MAX_VALUE_SIZE {
+        return Err(preflight_err(
+            DataStoreErrorCode::ContentTooBig,
+            format!("value is {} bytes (max {MAX_VALUE_SIZE})", data.len()),
+        ));
+    }
+    if serde_json::from_str::<serde::de::IgnoredAny>(data).is_err() {
+        return Err(preflight_err(
+            DataStoreErrorCode::ContentNotJson,
+            "value is not valid JSON".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 async fn handle_datastore_err<T>(res: Response) -> Result<T, Error> {
     let response_text = res.text().await?;
     match serde_json::from_str::<DataStoreErrorResponse>(&response_text) {
@@ -276,6 +431,51 @@ pub async fn get_entry<T: DeserializeOwned>(params: &GetEntryParams) -> Result<T
     handle_res::<T>(res).await
 }
 
+fn attributes_header(attributes: Option<&EntryAttributes>) -> String {
+    attributes
+        .map(|attributes| serde_json::Value::Object(attributes.clone()).to_string())
+        .unwrap_or_else(|| "{}".to_string())
+}
+
+fn header_string(res: &Response, name: &str) -> Option<String> {
+    res.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+fn build_entry_metadata(res: &Response) -> EntryMetadata {
+    let user_ids = header_string(res, "roblox-entry-userids")
+        .and_then(|ids| serde_json::from_str::<Vec<u64>>(&ids).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(RobloxUserId)
+        .collect();
+    EntryMetadata {
+        version: header_string(res, "roblox-entry-version"),
+        created_time: header_string(res, "roblox-entry-created-time"),
+        version_created_time: header_string(res, "roblox-entry-version-created-time"),
+        user_ids,
+        attributes: header_string(res, "roblox-entry-attributes")
+            .and_then(|attributes| serde_json::from_str(&attributes).ok()),
+        content_md5: header_string(res, "content-md5"),
+    }
+}
+
+/// Get the value of an entry as a string, along with the entry's metadata
+/// (version, user IDs, attributes, and checksum).
+pub async fn get_entry_with_metadata(
+    params: &GetEntryParams,
+) -> Result<GetEntryWithMetadataResponse, Error> {
+    let res = get_entry_response(params).await?;
+    if !res.status().is_success() {
+        return handle_datastore_err::<GetEntryWithMetadataResponse>(res).await;
+    }
+    let metadata = build_entry_metadata(&res);
+    let data = res.text().await?;
+    Ok(GetEntryWithMetadataResponse { data, metadata })
+}
+
 fn build_ids_csv(ids: &Option<Vec<RobloxUserId>>) -> String {
     ids.as_ref()
         .unwrap_or(&vec![])
@@ -313,10 +513,7 @@ pub async fn set_entry(params: &SetEntryParams) -> Result<SetEntryResponse, Erro
         )
         .header(
             "roblox-entry-attributes",
-            params
-                .roblox_entry_attributes
-                .as_ref()
-                .unwrap_or(&String::from("{}")),
+            attributes_header(params.roblox_entry_attributes.as_ref()),
         )
         .header("content-md5", get_checksum_base64(&params.data))
         .body(params.data.clone())
@@ -346,10 +543,7 @@ pub async fn increment_entry(params: &IncrementEntryParams) -> Result<f64, Error
         .header("roblox-entry-userids", format!("[{ids}]"))
         .header(
             "roblox-entry-attributes",
-            params
-                .roblox_entry_attributes
-                .as_ref()
-                .unwrap_or(&"{}".to_string()),
+            attributes_header(params.roblox_entry_attributes.as_ref()),
         )
         .query(&query)
         .send()
@@ -418,8 +612,7 @@ pub async fn list_entry_versions(
     handle_res::<ListEntryVersionsResponse>(res).await
 }
 
-/// Get the value of a specific entry version.
-pub async fn get_entry_version(params: &GetEntryVersionParams) -> Result<String, Error> {
+async fn get_entry_version_response(params: &GetEntryVersionParams) -> Result<Response, Error> {
     let client = reqwest::Client::new();
     let url = build_url(
         "/datastore/entries/entry/versions/version",
@@ -440,5 +633,25 @@ pub async fn get_entry_version(params: &GetEntryVersionParams) -> Result<String,
         .query(&query)
         .send()
         .await?;
+    Ok(res)
+}
+
+/// Get the value of a specific entry version.
+pub async fn get_entry_version(params: &GetEntryVersionParams) -> Result<String, Error> {
+    let res = get_entry_version_response(params).await?;
     handle_res_string(res).await
 }
+
+/// Get the value of a specific entry version, along with the metadata the
+/// entry had at that version.
+pub async fn get_entry_version_with_metadata(
+    params: &GetEntryVersionParams,
+) -> Result<GetEntryWithMetadataResponse, Error> {
+    let res = get_entry_version_response(params).await?;
+    if !res.status().is_success() {
+        return handle_datastore_err::<GetEntryWithMetadataResponse>(res).await;
+    }
+    let metadata = build_entry_metadata(&res);
+    let data = res.text().await?;
+    Ok(GetEntryWithMetadataResponse { data, metadata })
+}
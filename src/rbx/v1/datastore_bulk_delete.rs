@@ -0,0 +1,195 @@
+//! Deleting many DataStore entries at once.
+//!
+//! Keys are collected first with `collect_keys`, so they can be reviewed
+//! before `bulk_delete` removes them. Every value is written to a backup
+//! (NDJSON, see `datastore_dump`) before its key is deleted, so a deletion
+//! can be undone by importing the backup.
+use std::{io::Write, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    datastore::{is_entry_not_found, ListEntriesKey},
+    datastore_dump::{fetch_entry, DumpedEntry},
+    DataStoreDeleteEntry, DataStoreListEntries, RbxDataStore, ReturnLimit,
+};
+
+/// Which keys to delete.
+#[derive(Debug, Clone)]
+pub enum BulkDeleteKeys {
+    /// Every key starting with the prefix.
+    Prefix(String),
+    /// An explicit list of keys.
+    Keys(Vec<String>),
+    /// Every key in the scope (or in all scopes).
+    All,
+}
+
+pub struct DataStoreBulkDelete {
+    pub name: String,
+    pub scope: Option<String>,
+    /// List keys from all scopes. Ignored for an explicit key list.
+    pub all_scopes: bool,
+    pub keys: BulkDeleteKeys,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkDeleteStatus {
+    Deleted,
+    /// The key did not exist.
+    Missing,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDeletedEntry {
+    pub scope: String,
+    pub key: String,
+    pub status: BulkDeleteStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreBulkDeleteReport {
+    pub deleted: u64,
+    pub missing: u64,
+    pub failed: u64,
+    pub entries: Vec<BulkDeletedEntry>,
+}
+
+/// Collect the keys that `bulk_delete` would remove.
+pub async fn collect_keys(
+    datastore: &RbxDataStore,
+    params: &DataStoreBulkDelete,
+) -> Result<Vec<ListEntriesKey>, Error> {
+    let prefix = match &params.keys {
+        BulkDeleteKeys::Keys(keys) => {
+            let scope = params.scope.clone().unwrap_or_else(|| "global".to_string());
+            return Ok(keys
+                .iter()
+                .map(|key| ListEntriesKey {
+                    scope: scope.clone(),
+                    key: key.clone(),
+                })
+                .collect());
+        }
+        BulkDeleteKeys::Prefix(prefix) => Some(prefix.clone()),
+        BulkDeleteKeys::All => None,
+    };
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: params.name.clone(),
+        scope: params.scope.clone(),
+        all_scopes: params.all_scopes,
+        prefix,
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    let mut keys = vec![];
+    while let Some(page) = pager.next_page().await? {
+        keys.extend(page);
+    }
+    Ok(keys)
+}
+
+/// Delete the given keys, backing up each value into `backup` first.
+///
+/// Keys are processed in chunks of `concurrency`: the chunk's values are
+/// fetched and written (and flushed) to the backup before any of them are
+/// deleted. A key whose value could not be backed up is not deleted.
+pub async fn bulk_delete<W: Write>(
+    datastore: &RbxDataStore,
+    name: &str,
+    keys: Vec<ListEntriesKey>,
+    backup: &mut W,
+    concurrency: usize,
+) -> Result<DataStoreBulkDeleteReport, Error> {
+    let shared = Arc::new((datastore.clone(), name.to_string()));
+    let mut report = DataStoreBulkDeleteReport::default();
+    let concurrency = concurrency.max(1);
+    let mut remaining = keys.into_iter().peekable();
+
+    while remaining.peek().is_some() {
+        let chunk: Vec<ListEntriesKey> = remaining.by_ref().take(concurrency).collect();
+
+        let fetched = map_concurrent(chunk, concurrency, |key| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name) = shared.as_ref();
+                let res = fetch_entry(datastore, name, &key).await;
+                (key, res)
+            }
+        })
+        .await;
+
+        let mut to_delete: Vec<DumpedEntry> = vec![];
+        for (key, res) in fetched {
+            match res {
+                Ok(entry) => {
+                    writeln!(backup, "{}", serde_json::to_string(&entry)?)?;
+                    to_delete.push(entry);
+                }
+                Err(err) if is_entry_not_found(&err) => {
+                    report.missing += 1;
+                    report.entries.push(BulkDeletedEntry {
+                        scope: key.scope,
+                        key: key.key,
+                        status: BulkDeleteStatus::Missing,
+                        error: None,
+                    });
+                }
+                Err(err) => {
+                    report.failed += 1;
+                    report.entries.push(BulkDeletedEntry {
+                        scope: key.scope,
+                        key: key.key,
+                        status: BulkDeleteStatus::Failed,
+                        error: Some(format!("backup failed: {err}")),
+                    });
+                }
+            }
+        }
+        backup.flush()?;
+
+        let deleted = map_concurrent(to_delete, concurrency, |entry| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name) = shared.as_ref();
+                let res = datastore
+                    .delete_entry(&DataStoreDeleteEntry {
+                        name: name.clone(),
+                        scope: Some(entry.scope.clone()),
+                        key: entry.key.clone(),
+                    })
+                    .await;
+                (entry, res)
+            }
+        })
+        .await;
+
+        for (entry, res) in deleted {
+            let (status, error) = match res {
+                Ok(()) => {
+                    report.deleted += 1;
+                    (BulkDeleteStatus::Deleted, None)
+                }
+                Err(err) => {
+                    report.failed += 1;
+                    (BulkDeleteStatus::Failed, Some(err.to_string()))
+                }
+            };
+            report.entries.push(BulkDeletedEntry {
+                scope: entry.scope,
+                key: entry.key,
+                status,
+                error,
+            });
+        }
+    }
+
+    Ok(report)
+}
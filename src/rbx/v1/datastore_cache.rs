@@ -0,0 +1,354 @@
+//! Read-through caching of DataStore entries.
+//!
+//! When a cache is set on an `RbxDataStore` (see `RbxDataStore::with_cache`),
+//! `get_entry`, `get_entry_string` and `get_entry_with_metadata` are served
+//! from the cache when possible, and `set_entry`, `increment_entry` and
+//! `delete_entry` invalidate the key they write, both before and after the
+//! write (whether or not it succeeded, since a failed request may still have
+//! been applied). Writes made by other servers are not seen until the cached
+//! entry expires, unless cached entries are validated by version.
+//!
+//! Bulk operations (copy, export, bulk delete backups, restore, erasure)
+//! always read through to Roblox, so they never capture a cached value.
+//!
+//! `MemoryCache` keeps entries in memory. Other backends (e.g. Redis) can
+//! implement `DataStoreCache`; entries are serializable for that purpose.
+//!
+//! ```rust,no_run
+//! use std::{sync::Arc, time::Duration};
+//! use rbxcloud::rbx::{
+//!     types::UniverseId,
+//!     v1::{datastore_cache::MemoryCache, RbxCloud},
+//! };
+//!
+//! let cache = Arc::new(MemoryCache::new(Duration::from_secs(30), 10_000));
+//! let datastore = RbxCloud::new("API_KEY")
+//!     .datastore(UniverseId(9876543210))
+//!     .with_cache(cache, false);
+//! ```
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, types::UniverseId};
+
+use super::{
+    datastore::{self, GetEntryParams, GetEntryWithMetadataResponse},
+    DataStoreGetEntry, DataStoreListEntryVersions, RbxDataStore, ReturnLimit,
+};
+
+/// Future returned by `DataStoreCache` methods.
+pub type CacheFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+/// Identifies a cached entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheKey {
+    pub universe_id: u64,
+    pub name: String,
+    pub scope: String,
+    pub key: String,
+}
+
+impl CacheKey {
+    pub fn new(universe_id: UniverseId, name: &str, scope: Option<&str>, key: &str) -> Self {
+        Self {
+            universe_id: universe_id.0,
+            name: name.to_string(),
+            scope: scope.unwrap_or("global").to_string(),
+            key: key.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}",
+            self.universe_id, self.name, self.scope, self.key
+        )
+    }
+}
+
+/// Storage for cached DataStore entries.
+///
+/// Errors from `get` and `put` are treated as cache misses. Errors from
+/// `invalidate` are returned to the caller of the write, since the cache may
+/// then hold a stale value.
+pub trait DataStoreCache: Send + Sync {
+    fn get<'a>(
+        &'a self,
+        key: &'a CacheKey,
+    ) -> CacheFuture<'a, Option<GetEntryWithMetadataResponse>>;
+
+    fn put<'a>(
+        &'a self,
+        key: &'a CacheKey,
+        entry: &'a GetEntryWithMetadataResponse,
+    ) -> CacheFuture<'a, ()>;
+
+    fn invalidate<'a>(&'a self, key: &'a CacheKey) -> CacheFuture<'a, ()>;
+}
+
+struct MemoryEntry {
+    entry: GetEntryWithMetadataResponse,
+    expires: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct MemoryCacheState {
+    entries: HashMap<CacheKey, MemoryEntry>,
+    /// Keys by last use, oldest first.
+    recency: BTreeMap<u64, CacheKey>,
+    size: usize,
+    clock: u64,
+}
+
+impl MemoryCacheState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(old) = self.entries.remove(key) {
+            self.size -= old.entry.data.len();
+            self.recency.remove(&old.last_used);
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            if let Some(old) = self.entries.remove(&key) {
+                self.size -= old.entry.data.len();
+            }
+        }
+    }
+}
+
+/// In-memory cache with a time to live, bounded by entry count and,
+/// optionally, by total value size. The least recently used entries are
+/// evicted first.
+pub struct MemoryCache {
+    ttl: Duration,
+    max_entries: usize,
+    max_bytes: Option<usize>,
+    state: Mutex<MemoryCacheState>,
+}
+
+impl MemoryCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            max_bytes: None,
+            state: Mutex::new(MemoryCacheState::default()),
+        }
+    }
+
+    /// Also bound the total size of cached values.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Number of cached entries, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .expect("cache lock poisoned")
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every entry.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().expect("cache lock poisoned");
+        state.entries.clear();
+        state.recency.clear();
+        state.size = 0;
+    }
+
+    fn get_sync(&self, key: &CacheKey) -> Option<GetEntryWithMetadataResponse> {
+        let mut state = self.state.lock().expect("cache lock poisoned");
+        let clock = state.tick();
+        match state.entries.get_mut(key) {
+            Some(e) if e.expires > Instant::now() => {
+                let previous = std::mem::replace(&mut e.last_used, clock);
+                let entry = e.entry.clone();
+                state.recency.remove(&previous);
+                state.recency.insert(clock, key.clone());
+                Some(entry)
+            }
+            Some(_) => {
+                state.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put_sync(&self, key: &CacheKey, entry: &GetEntryWithMetadataResponse) {
+        let size = entry.data.len();
+        if self.max_entries == 0 || self.max_bytes.is_some_and(|max| size > max) {
+            return;
+        }
+        let mut state = self.state.lock().expect("cache lock poisoned");
+        state.remove(key);
+        while state.entries.len() >= self.max_entries
+            || self
+                .max_bytes
+                .is_some_and(|max| state.size + size > max && !state.entries.is_empty())
+        {
+            state.evict_least_recently_used();
+        }
+        let last_used = state.tick();
+        state.recency.insert(last_used, key.clone());
+        state.size += size;
+        state.entries.insert(
+            key.clone(),
+            MemoryEntry {
+                entry: entry.clone(),
+                expires: Instant::now() + self.ttl,
+                last_used,
+            },
+        );
+    }
+}
+
+impl DataStoreCache for MemoryCache {
+    fn get<'a>(
+        &'a self,
+        key: &'a CacheKey,
+    ) -> CacheFuture<'a, Option<GetEntryWithMetadataResponse>> {
+        Box::pin(async move { Ok(self.get_sync(key)) })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a CacheKey,
+        entry: &'a GetEntryWithMetadataResponse,
+    ) -> CacheFuture<'a, ()> {
+        Box::pin(async move {
+            self.put_sync(key, entry);
+            Ok(())
+        })
+    }
+
+    fn invalidate<'a>(&'a self, key: &'a CacheKey) -> CacheFuture<'a, ()> {
+        Box::pin(async move {
+            self.state.lock().expect("cache lock poisoned").remove(key);
+            Ok(())
+        })
+    }
+}
+
+/// Whether `version` is still the latest version of the key. Listing
+/// versions does not count against the read quota.
+async fn is_latest_version(
+    datastore: &RbxDataStore,
+    params: &DataStoreGetEntry,
+    version: Option<&str>,
+) -> Result<bool, Error> {
+    let Some(version) = version else {
+        return Ok(false);
+    };
+    let res = datastore
+        .list_entry_versions(&DataStoreListEntryVersions {
+            name: params.name.clone(),
+            scope: params.scope.clone(),
+            key: params.key.clone(),
+            start_time: None,
+            end_time: None,
+            sort_order: "Descending".to_string(),
+            limit: ReturnLimit(1),
+            cursor: None,
+        })
+        .await?;
+    Ok(res
+        .versions
+        .first()
+        .is_some_and(|latest| latest.version == version && !latest.deleted))
+}
+
+/// Get an entry through the cache, fetching and caching it on a miss.
+pub(crate) async fn get_cached(
+    datastore: &RbxDataStore,
+    cache: &dyn DataStoreCache,
+    params: &DataStoreGetEntry,
+) -> Result<GetEntryWithMetadataResponse, Error> {
+    let key = CacheKey::new(
+        datastore.universe_id,
+        &params.name,
+        params.scope.as_deref(),
+        &params.key,
+    );
+    if let Ok(Some(entry)) = cache.get(&key).await {
+        if !datastore.validate_cached_versions
+            || is_latest_version(datastore, params, entry.metadata.version.as_deref()).await?
+        {
+            return Ok(entry);
+        }
+    }
+    let entry = datastore::get_entry_with_metadata(&GetEntryParams {
+        api_key: datastore.api_key.clone(),
+        universe_id: datastore.universe_id,
+        datastore_name: params.name.clone(),
+        scope: params.scope.clone(),
+        key: params.key.clone(),
+    })
+    .await?;
+    let _ = cache.put(&key, &entry).await;
+    Ok(entry)
+}
+
+/// Drop a key from the cache.
+async fn invalidate(
+    datastore: &RbxDataStore,
+    name: &str,
+    scope: Option<&str>,
+    key: &str,
+) -> Result<(), Error> {
+    match &datastore.cache {
+        Some(cache) => {
+            cache
+                .invalidate(&CacheKey::new(datastore.universe_id, name, scope, key))
+                .await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Run a write to a key, dropping the key from the cache before the write
+/// and again after it, whatever its result: a request that failed or timed
+/// out may still have been applied, and a read racing the write may have
+/// cached the old value in the meantime.
+pub(crate) async fn invalidating_write<T, F>(
+    datastore: &RbxDataStore,
+    name: &str,
+    scope: Option<&str>,
+    key: &str,
+    write: F,
+) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    invalidate(datastore, name, scope, key).await?;
+    let res = write.await;
+    let invalidated = invalidate(datastore, name, scope, key).await;
+    let value = res?;
+    invalidated?;
+    Ok(value)
+}
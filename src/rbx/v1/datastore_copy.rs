@@ -0,0 +1,296 @@
+//! Copying and migrating entries between DataStores.
+//!
+//! Typically, these operations should be consumed through the `RbxDataStore`
+//! struct, obtained through the `RbxCloud` struct.
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rbx::{
+    error::Error,
+    util::{get_checksum_base64, map_concurrent},
+};
+
+use super::{
+    datastore::{is_entry_not_found, ListEntriesKey},
+    DataStoreDeleteEntry, DataStoreGetEntry, DataStoreListEntries, DataStoreSetEntry, RbxDataStore,
+    ReturnLimit,
+};
+
+/// Transforms an entry value before it is written to the destination.
+///
+/// Returning `Ok(None)` skips the entry.
+pub type EntryTransform =
+    Arc<dyn Fn(&ListEntriesKey, Value) -> Result<Option<Value>, Error> + Send + Sync>;
+
+pub struct DataStoreCopyEntries {
+    /// Source DataStore name.
+    pub name: String,
+    /// Source scope.
+    pub scope: Option<String>,
+    /// Copy keys from all scopes of the source DataStore.
+    pub all_scopes: bool,
+    /// Only copy keys with this prefix.
+    pub prefix: Option<String>,
+    /// Destination DataStore name.
+    pub destination_name: String,
+    /// Destination scope. If `None`, each entry keeps its source scope.
+    pub destination_scope: Option<String>,
+    /// Optional transform applied to each value.
+    pub transform: Option<EntryTransform>,
+    /// Do not overwrite entries that already exist in the destination.
+    pub skip_existing: bool,
+    /// Read each written entry back and compare MD5 checksums.
+    pub verify: bool,
+    /// Delete each source entry once it has been copied (and verified, if enabled).
+    pub delete_source: bool,
+    /// Report what would be copied without writing anything.
+    pub dry_run: bool,
+    /// Maximum number of entries processed at once.
+    pub concurrency: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CopyStatus {
+    Copied,
+    Skipped,
+    Filtered,
+    DryRun,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CopiedEntry {
+    pub scope: String,
+    pub key: String,
+    pub destination_scope: String,
+    pub status: CopyStatus,
+    pub verified: Option<bool>,
+    pub source_deleted: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreCopyReport {
+    pub copied: u64,
+    pub skipped: u64,
+    pub filtered: u64,
+    pub failed: u64,
+    pub verification_failures: u64,
+    pub source_deleted: u64,
+    pub entries: Vec<CopiedEntry>,
+}
+
+impl DataStoreCopyReport {
+    fn record(&mut self, entry: CopiedEntry) {
+        match entry.status {
+            CopyStatus::Copied | CopyStatus::DryRun => self.copied += 1,
+            CopyStatus::Skipped => self.skipped += 1,
+            CopyStatus::Filtered => self.filtered += 1,
+            CopyStatus::Failed => self.failed += 1,
+        }
+        if entry.verified == Some(false) {
+            self.verification_failures += 1;
+        }
+        if entry.source_deleted {
+            self.source_deleted += 1;
+        }
+        self.entries.push(entry);
+    }
+}
+
+struct CopyJob {
+    source: RbxDataStore,
+    destination: RbxDataStore,
+    name: String,
+    destination_name: String,
+    destination_scope: Option<String>,
+    transform: Option<EntryTransform>,
+    skip_existing: bool,
+    verify: bool,
+    delete_source: bool,
+    dry_run: bool,
+}
+
+impl CopyJob {
+    fn is_same_entry(&self, scope: &str, destination_scope: &str) -> bool {
+        self.source.universe_id.0 == self.destination.universe_id.0
+            && self.name == self.destination_name
+            && scope == destination_scope
+    }
+
+    async fn copy_entry(&self, key: &ListEntriesKey, entry: &mut CopiedEntry) -> Result<(), Error> {
+        if self.is_same_entry(&key.scope, &entry.destination_scope) {
+            entry.status = CopyStatus::Failed;
+            entry.error = Some("source and destination are the same entry".to_string());
+            return Ok(());
+        }
+
+        let source = self
+            .source
+            .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+                name: self.name.clone(),
+                scope: Some(key.scope.clone()),
+                key: key.key.clone(),
+            })
+            .await?;
+
+        let data = match &self.transform {
+            Some(transform) => {
+                let value = serde_json::from_str::<Value>(&source.data)?;
+                match transform(key, value)? {
+                    Some(value) => serde_json::to_string(&value)?,
+                    None => {
+                        entry.status = CopyStatus::Filtered;
+                        return Ok(());
+                    }
+                }
+            }
+            None => source.data,
+        };
+
+        let destination_entry = DataStoreGetEntry {
+            name: self.destination_name.clone(),
+            scope: Some(entry.destination_scope.clone()),
+            key: key.key.clone(),
+        };
+
+        if self.skip_existing {
+            // Only a missing destination is written; any other error fails
+            // the key rather than risking an overwrite.
+            match self
+                .destination
+                .get_entry_with_metadata_uncached(&destination_entry)
+                .await
+            {
+                Ok(_) => {
+                    entry.status = CopyStatus::Skipped;
+                    return Ok(());
+                }
+                Err(err) if is_entry_not_found(&err) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        if self.dry_run {
+            self.destination
+                .validate_entry(&self.destination_name, &key.key, &data)?;
+            entry.status = CopyStatus::DryRun;
+            return Ok(());
+        }
+
+        let user_ids = source.metadata.user_ids;
+        self.destination
+            .set_entry(&DataStoreSetEntry {
+                name: self.destination_name.clone(),
+                scope: Some(entry.destination_scope.clone()),
+                key: key.key.clone(),
+                match_version: None,
+                exclusive_create: None,
+                roblox_entry_user_ids: (!user_ids.is_empty()).then_some(user_ids),
+                roblox_entry_attributes: source.metadata.attributes,
+                data: data.clone(),
+            })
+            .await?;
+        entry.status = CopyStatus::Copied;
+
+        if self.verify {
+            let written = self
+                .destination
+                .get_entry_with_metadata_uncached(&destination_entry)
+                .await?;
+            let verified = match written.metadata.content_md5 {
+                Some(md5) => md5 == get_checksum_base64(&data),
+                None => written.data == data,
+            };
+            entry.verified = Some(verified);
+            if !verified {
+                entry.error = Some("destination checksum does not match".to_string());
+                return Ok(());
+            }
+        }
+
+        if self.delete_source {
+            self.source
+                .delete_entry(&DataStoreDeleteEntry {
+                    name: self.name.clone(),
+                    scope: Some(key.scope.clone()),
+                    key: key.key.clone(),
+                })
+                .await?;
+            entry.source_deleted = true;
+        }
+
+        Ok(())
+    }
+
+    async fn run(&self, key: ListEntriesKey) -> CopiedEntry {
+        let mut entry = CopiedEntry {
+            destination_scope: self
+                .destination_scope
+                .clone()
+                .unwrap_or_else(|| key.scope.clone()),
+            scope: key.scope.clone(),
+            key: key.key.clone(),
+            status: CopyStatus::Failed,
+            verified: None,
+            source_deleted: false,
+            error: None,
+        };
+        if let Err(err) = self.copy_entry(&key, &mut entry).await {
+            entry.status = CopyStatus::Failed;
+            entry.error = Some(err.to_string());
+        }
+        entry
+    }
+}
+
+/// Copy every matching entry from `source` into `destination`.
+///
+/// Failures of individual entries are recorded in the report rather than
+/// aborting the whole copy. An error is only returned if the source keys
+/// cannot be listed.
+pub async fn copy_entries(
+    source: &RbxDataStore,
+    destination: &RbxDataStore,
+    params: &DataStoreCopyEntries,
+) -> Result<DataStoreCopyReport, Error> {
+    let job = Arc::new(CopyJob {
+        source: source.clone(),
+        destination: destination.clone(),
+        name: params.name.clone(),
+        destination_name: params.destination_name.clone(),
+        destination_scope: params.destination_scope.clone(),
+        transform: params.transform.clone(),
+        skip_existing: params.skip_existing,
+        verify: params.verify,
+        delete_source: params.delete_source && !params.dry_run,
+        dry_run: params.dry_run,
+    });
+
+    let mut pager = source.key_pager(DataStoreListEntries {
+        name: params.name.clone(),
+        scope: params.scope.clone(),
+        all_scopes: params.all_scopes,
+        prefix: params.prefix.clone(),
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+
+    let mut report = DataStoreCopyReport::default();
+    while let Some(keys) = pager.next_page().await? {
+        let entries = map_concurrent(keys, params.concurrency, |key| {
+            let job = job.clone();
+            async move { job.run(key).await }
+        })
+        .await;
+        for entry in entries {
+            report.record(entry);
+        }
+    }
+    Ok(report)
+}
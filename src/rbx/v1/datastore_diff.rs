@@ -0,0 +1,192 @@
+//! Comparing the contents of DataStores, scopes, universes, and NDJSON dumps.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rbx::{
+    error::Error,
+    json_diff::{diff, JsonChange},
+};
+
+use super::{
+    datastore_dump::{load_entries, DataStoreExportEntries, DumpedEntry, EntryReadError},
+    RbxDataStore,
+};
+
+/// One side of a diff.
+pub enum DiffSource {
+    /// Entries read from a live DataStore.
+    Live {
+        datastore: RbxDataStore,
+        params: DataStoreExportEntries,
+    },
+    /// Entries read from an NDJSON dump (see `datastore_dump::read_dump`).
+    Dump(Vec<DumpedEntry>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyDiffStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDiff {
+    /// The key, prefixed with `scope/` when scopes are compared.
+    pub key: String,
+    pub status: KeyDiffStatus,
+    /// Structural changes of the value (only for changed keys).
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub changes: Vec<JsonChange>,
+    /// The value of a removed key.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub old: Option<Value>,
+    /// The value of an added key.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub new: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreDiffReport {
+    pub added: u64,
+    pub removed: u64,
+    pub changed: u64,
+    pub unchanged: u64,
+    pub keys: Vec<KeyDiff>,
+    /// Entries of either side that could not be read, and were left out of
+    /// the comparison.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<EntryReadError>,
+}
+
+async fn load_source(
+    source: &DiffSource,
+    errors: &mut Vec<EntryReadError>,
+) -> Result<Vec<DumpedEntry>, Error> {
+    match source {
+        DiffSource::Live { datastore, params } => {
+            let loaded = load_entries(datastore, params).await?;
+            errors.extend(loaded.errors);
+            Ok(loaded.entries)
+        }
+        DiffSource::Dump(entries) => Ok(entries.clone()),
+    }
+}
+
+fn index_key(scope: &str, key: &str, compare_scopes: bool) -> String {
+    if compare_scopes {
+        format!("{scope}/{key}")
+    } else {
+        key.to_string()
+    }
+}
+
+fn index_entries(
+    entries: Vec<DumpedEntry>,
+    compare_scopes: bool,
+) -> Result<BTreeMap<String, Value>, Error> {
+    let mut index = BTreeMap::new();
+    let mut scopes: BTreeMap<String, String> = BTreeMap::new();
+    for entry in entries {
+        if compare_scopes {
+            index.insert(index_key(&entry.scope, &entry.key, true), entry.value);
+            continue;
+        }
+        if let Some(scope) = scopes.insert(entry.key.clone(), entry.scope.clone()) {
+            return Err(Error::DiffError(format!(
+                "key {} exists in scopes {scope} and {}; compare scopes to tell them apart",
+                entry.key, entry.scope
+            )));
+        }
+        index.insert(entry.key, entry.value);
+    }
+    Ok(index)
+}
+
+/// Compare two sources, going from `old` to `new`.
+///
+/// When `compare_scopes` is false, entries are matched by key alone, which
+/// allows comparing two different scopes with each other. Each side must then
+/// hold a single scope: live sources reading all scopes are rejected, as are
+/// keys found in more than one scope.
+///
+/// Entries that cannot be read are listed in the report and left out of the
+/// comparison.
+pub async fn diff_entries(
+    old: &DiffSource,
+    new: &DiffSource,
+    compare_scopes: bool,
+) -> Result<DataStoreDiffReport, Error> {
+    if !compare_scopes
+        && [old, new]
+            .iter()
+            .any(|source| matches!(source, DiffSource::Live { params, .. } if params.all_scopes))
+    {
+        return Err(Error::DiffError(
+            "comparing all scopes requires compare_scopes, since keys in different scopes \
+             would be matched with each other"
+                .to_string(),
+        ));
+    }
+    let mut report = DataStoreDiffReport::default();
+    let mut old_entries =
+        index_entries(load_source(old, &mut report.errors).await?, compare_scopes)?;
+    let mut new_entries =
+        index_entries(load_source(new, &mut report.errors).await?, compare_scopes)?;
+    // An entry unread on one side would otherwise show up as added or
+    // removed, so it is dropped from both.
+    for err in &report.errors {
+        let key = index_key(&err.scope, &err.key, compare_scopes);
+        old_entries.remove(&key);
+        new_entries.remove(&key);
+    }
+
+    let mut keys = vec![];
+    for (key, old_value) in old_entries {
+        match new_entries.remove(&key) {
+            Some(new_value) => {
+                let changes = diff(&old_value, &new_value);
+                if changes.is_empty() {
+                    report.unchanged += 1;
+                } else {
+                    report.changed += 1;
+                    keys.push(KeyDiff {
+                        key,
+                        status: KeyDiffStatus::Changed,
+                        changes,
+                        old: None,
+                        new: None,
+                    });
+                }
+            }
+            None => {
+                report.removed += 1;
+                keys.push(KeyDiff {
+                    key,
+                    status: KeyDiffStatus::Removed,
+                    changes: vec![],
+                    old: Some(old_value),
+                    new: None,
+                });
+            }
+        }
+    }
+    for (key, new_value) in new_entries {
+        report.added += 1;
+        keys.push(KeyDiff {
+            key,
+            status: KeyDiffStatus::Added,
+            changes: vec![],
+            old: None,
+            new: Some(new_value),
+        });
+    }
+    keys.sort_by(|a, b| a.key.cmp(&b.key));
+    report.keys = keys;
+    Ok(report)
+}
@@ -0,0 +1,316 @@
+//! Exporting DataStore entries to, and importing them from, newline-delimited
+//! JSON (NDJSON).
+//!
+//! Each line of a dump is one `DumpedEntry`:
+//!
+//! ```json
+//! {"scope":"global","key":"Player_1","value":{"coins":10},"userIds":[1],"attributes":null,"version":"..."}
+//! ```
+use std::{
+    io::{BufRead, Write},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    datastore::{is_entry_not_found, EntryAttributes, ListEntriesKey},
+    DataStoreGetEntry, DataStoreListEntries, DataStoreSetEntry, RbxDataStore, ReturnLimit,
+    RobloxUserId,
+};
+
+/// A single exported DataStore entry.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpedEntry {
+    pub scope: String,
+    pub key: String,
+    pub value: Value,
+    #[serde(default)]
+    pub user_ids: Vec<RobloxUserId>,
+    #[serde(default)]
+    pub attributes: Option<EntryAttributes>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+pub struct DataStoreExportEntries {
+    pub name: String,
+    pub scope: Option<String>,
+    pub all_scopes: bool,
+    pub prefix: Option<String>,
+    /// Maximum number of entries fetched at once.
+    pub concurrency: usize,
+}
+
+pub struct DataStoreImportEntries {
+    pub name: String,
+    /// Write every entry into this scope instead of the scope recorded in the dump.
+    pub scope: Option<String>,
+    /// Do not overwrite entries that already exist.
+    pub skip_existing: bool,
+    /// Maximum number of entries written at once.
+    pub concurrency: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStatus {
+    Imported,
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedEntry {
+    pub scope: String,
+    pub key: String,
+    pub status: ImportStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreImportReport {
+    pub imported: u64,
+    pub skipped: u64,
+    pub failed: u64,
+    pub entries: Vec<ImportedEntry>,
+}
+
+/// An entry that could not be read, for example because its value is not
+/// JSON or because the key was deleted while the DataStore was being read.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryReadError {
+    pub scope: String,
+    pub key: String,
+    pub error: String,
+}
+
+/// Entries fetched by `load_entries`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedEntries {
+    pub entries: Vec<DumpedEntry>,
+    pub errors: Vec<EntryReadError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreExportReport {
+    pub exported: u64,
+    pub errors: Vec<EntryReadError>,
+}
+
+pub(crate) async fn fetch_entry(
+    datastore: &RbxDataStore,
+    name: &str,
+    key: &ListEntriesKey,
+) -> Result<DumpedEntry, Error> {
+    let entry = datastore
+        .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+            name: name.to_string(),
+            scope: Some(key.scope.clone()),
+            key: key.key.clone(),
+        })
+        .await?;
+    Ok(DumpedEntry {
+        scope: key.scope.clone(),
+        key: key.key.clone(),
+        value: serde_json::from_str(&entry.data)?,
+        user_ids: entry.metadata.user_ids,
+        attributes: entry.metadata.attributes,
+        version: entry.metadata.version,
+    })
+}
+
+/// Fetch every matching entry, calling `on_page` with each page of entries
+/// in key-listing order.
+///
+/// Entries that cannot be read are left out of the pages and returned, so
+/// that one bad entry does not end the scan; a failure to list keys does.
+pub async fn for_each_entry_page<F>(
+    datastore: &RbxDataStore,
+    params: &DataStoreExportEntries,
+    mut on_page: F,
+) -> Result<Vec<EntryReadError>, Error>
+where
+    F: FnMut(Vec<DumpedEntry>) -> Result<(), Error>,
+{
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: params.name.clone(),
+        scope: params.scope.clone(),
+        all_scopes: params.all_scopes,
+        prefix: params.prefix.clone(),
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    let shared = Arc::new((datastore.clone(), params.name.clone()));
+    let mut errors = vec![];
+    while let Some(keys) = pager.next_page().await? {
+        let results = map_concurrent(keys, params.concurrency, |key| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name) = shared.as_ref();
+                let res = fetch_entry(datastore, name, &key).await;
+                (key, res)
+            }
+        })
+        .await;
+        let mut entries = Vec::with_capacity(results.len());
+        for (key, res) in results {
+            match res {
+                Ok(entry) => entries.push(entry),
+                Err(err) => errors.push(EntryReadError {
+                    scope: key.scope,
+                    key: key.key,
+                    error: if is_entry_not_found(&err) {
+                        "entry was deleted while being read".to_string()
+                    } else {
+                        err.to_string()
+                    },
+                }),
+            }
+        }
+        on_page(entries)?;
+    }
+    Ok(errors)
+}
+
+/// Fetch every matching entry into memory.
+pub async fn load_entries(
+    datastore: &RbxDataStore,
+    params: &DataStoreExportEntries,
+) -> Result<LoadedEntries, Error> {
+    let mut entries = vec![];
+    let errors = for_each_entry_page(datastore, params, |page| {
+        entries.extend(page);
+        Ok(())
+    })
+    .await?;
+    Ok(LoadedEntries { entries, errors })
+}
+
+/// Export every matching entry as NDJSON into `writer`. Entries that cannot
+/// be read are listed in the report instead of being written.
+pub async fn export_entries<W: Write>(
+    datastore: &RbxDataStore,
+    params: &DataStoreExportEntries,
+    writer: &mut W,
+) -> Result<DataStoreExportReport, Error> {
+    let mut exported = 0;
+    let errors = for_each_entry_page(datastore, params, |page| {
+        for entry in page {
+            writeln!(writer, "{}", serde_json::to_string(&entry)?)?;
+            exported += 1;
+        }
+        Ok(())
+    })
+    .await?;
+    Ok(DataStoreExportReport { exported, errors })
+}
+
+/// Read entries from an NDJSON dump. Blank lines are ignored.
+pub fn read_dump<R: BufRead>(reader: R) -> Result<Vec<DumpedEntry>, Error> {
+    let mut entries = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        entries.push(serde_json::from_str::<DumpedEntry>(&line)?);
+    }
+    Ok(entries)
+}
+
+async fn import_entry(
+    datastore: &RbxDataStore,
+    name: &str,
+    scope: &str,
+    skip_existing: bool,
+    entry: &DumpedEntry,
+) -> Result<ImportStatus, Error> {
+    if skip_existing {
+        // Only a missing key is written; any other error fails the entry
+        // rather than risking an overwrite.
+        match datastore
+            .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+                name: name.to_string(),
+                scope: Some(scope.to_string()),
+                key: entry.key.clone(),
+            })
+            .await
+        {
+            Ok(_) => return Ok(ImportStatus::Skipped),
+            Err(err) if is_entry_not_found(&err) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    datastore
+        .set_entry(&DataStoreSetEntry {
+            name: name.to_string(),
+            scope: Some(scope.to_string()),
+            key: entry.key.clone(),
+            match_version: None,
+            // A key created since the check above makes the write fail
+            // instead of being overwritten.
+            exclusive_create: skip_existing.then_some(true),
+            roblox_entry_user_ids: (!entry.user_ids.is_empty()).then(|| entry.user_ids.clone()),
+            roblox_entry_attributes: entry.attributes.clone(),
+            data: serde_json::to_string(&entry.value)?,
+        })
+        .await?;
+    Ok(ImportStatus::Imported)
+}
+
+/// Write dumped entries (e.g. from `read_dump`) into a DataStore, including
+/// their user IDs and attributes.
+///
+/// Failures of individual entries are recorded in the report.
+pub async fn import_entries(
+    datastore: &RbxDataStore,
+    params: &DataStoreImportEntries,
+    entries: Vec<DumpedEntry>,
+) -> Result<DataStoreImportReport, Error> {
+    let shared = Arc::new((
+        datastore.clone(),
+        params.name.clone(),
+        params.scope.clone(),
+        params.skip_existing,
+    ));
+    let results = map_concurrent(entries, params.concurrency, |entry| {
+        let shared = shared.clone();
+        async move {
+            let (datastore, name, scope, skip_existing) = shared.as_ref();
+            let scope = scope.clone().unwrap_or_else(|| entry.scope.clone());
+            let res = import_entry(datastore, name, &scope, *skip_existing, &entry).await;
+            let (status, error) = match res {
+                Ok(status) => (status, None),
+                Err(err) => (ImportStatus::Failed, Some(err.to_string())),
+            };
+            ImportedEntry {
+                scope,
+                key: entry.key,
+                status,
+                error,
+            }
+        }
+    })
+    .await;
+
+    let mut report = DataStoreImportReport::default();
+    for entry in results {
+        match entry.status {
+            ImportStatus::Imported => report.imported += 1,
+            ImportStatus::Skipped => report.skipped += 1,
+            ImportStatus::Failed => report.failed += 1,
+        }
+        report.entries.push(entry);
+    }
+    Ok(report)
+}
@@ -0,0 +1,449 @@
+//! Erasing every entry associated with a user (right-to-erasure requests).
+//!
+//! Entries are found in two ways:
+//!
+//! - Key templates, such as `Player_{id}`, where `{id}` is replaced with the
+//!   user ID. Templates work for both DataStores and OrderedDataStores.
+//! - A full scan of DataStores, matching entries whose `roblox-entry-userids`
+//!   metadata contains the user ID. OrderedDataStore entries carry no user ID
+//!   metadata, so scanned OrderedDataStores are matched on the entry ID
+//!   being the user ID instead.
+//!
+//! Like restores, an erasure happens in two steps: `plan_erasure` finds the
+//! matching entries without deleting anything, and `apply_erasure` deletes
+//! them. The resulting report is meant to be kept as an audit record, so it
+//! holds the location and version of every erased entry, but never its value.
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    datastore::{is_entry_not_found, ListEntriesKey},
+    DataStoreDeleteEntry, DataStoreGetEntry, DataStoreListEntries, OrderedDataStoreEntry,
+    OrderedDataStoreListEntries, PageSize, RbxDataStore, RbxOrderedDataStore, ReturnLimit,
+    RobloxUserId,
+};
+
+/// A key to check, built from a template by replacing `{id}` with the user ID.
+#[derive(Debug, Clone)]
+pub struct ErasureKeyTemplate {
+    pub name: String,
+    pub scope: Option<String>,
+    pub template: String,
+}
+
+impl ErasureKeyTemplate {
+    /// The key for the given user.
+    pub fn render(&self, user_id: RobloxUserId) -> String {
+        self.template.replace("{id}", &user_id.to_string())
+    }
+}
+
+/// A DataStore or OrderedDataStore scope to scan.
+#[derive(Debug, Clone)]
+pub struct ErasureScanTarget {
+    pub name: String,
+    pub scope: Option<String>,
+}
+
+pub struct DataStoreErasure {
+    pub user_id: RobloxUserId,
+    /// DataStore keys to erase.
+    pub templates: Vec<ErasureKeyTemplate>,
+    /// OrderedDataStore entries to erase.
+    pub ordered_templates: Vec<ErasureKeyTemplate>,
+    /// Scan DataStores for entries whose user ID metadata contains the user.
+    pub scan: bool,
+    /// DataStores to scan. Every DataStore in the universe when `None`.
+    pub scan_datastores: Option<Vec<String>>,
+    /// OrderedDataStores to scan for entries whose ID is the user ID.
+    pub scan_ordered_datastores: Vec<ErasureScanTarget>,
+    /// Maximum number of entries fetched or deleted at once.
+    pub concurrency: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ErasureSource {
+    DataStore,
+    OrderedDataStore,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErasureMatch {
+    /// The key was built from a key template.
+    KeyTemplate,
+    /// The entry's `roblox-entry-userids` metadata contains the user ID.
+    UserIdMetadata,
+    /// The OrderedDataStore entry ID is the user ID.
+    EntryId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErasureStatus {
+    /// Found, but not deleted yet.
+    Pending,
+    Deleted,
+    /// The entry no longer existed when it was deleted.
+    Missing,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErasedEntry {
+    pub source: ErasureSource,
+    pub name: String,
+    pub scope: String,
+    pub key: String,
+    pub matched_by: ErasureMatch,
+    /// Version of the entry when it was found (DataStores only).
+    pub version: Option<String>,
+    pub status: ErasureStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreErasureReport {
+    pub user_id: RobloxUserId,
+    pub universe_id: u64,
+    /// When the matching entries were collected.
+    pub planned_time: String,
+    /// When the entries were deleted; `None` for a plan.
+    pub applied_time: Option<String>,
+    /// Keys and entries that were checked.
+    pub checked: u64,
+    pub entries: Vec<ErasedEntry>,
+    /// Lookups that failed while planning. Entries behind them may have been
+    /// missed, so the erasure should be retried.
+    pub errors: Vec<String>,
+}
+
+impl DataStoreErasureReport {
+    /// Number of entries with the given status.
+    pub fn count(&self, status: ErasureStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+}
+
+fn is_ordered_not_found(err: &Error) -> bool {
+    matches!(err, Error::HttpStatusError { code: 404, .. })
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Check a DataStore key, returning its version if it exists and (when
+/// `user_id` is given) its metadata contains the user.
+async fn find_entry(
+    datastore: &RbxDataStore,
+    name: &str,
+    key: &ListEntriesKey,
+    user_id: Option<RobloxUserId>,
+) -> Result<Option<Option<String>>, Error> {
+    let res = datastore
+        .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+            name: name.to_string(),
+            scope: Some(key.scope.clone()),
+            key: key.key.clone(),
+        })
+        .await;
+    match res {
+        Ok(entry) => match user_id {
+            Some(user_id) if !entry.metadata.user_ids.contains(&user_id) => Ok(None),
+            _ => Ok(Some(entry.metadata.version)),
+        },
+        Err(err) if is_entry_not_found(&err) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn find_ordered_entry(
+    ordered: &RbxOrderedDataStore,
+    template: &ErasureKeyTemplate,
+    id: &str,
+) -> Result<bool, Error> {
+    let res = ordered
+        .get_entry(&OrderedDataStoreEntry {
+            name: template.name.clone(),
+            scope: template.scope.clone(),
+            id: id.to_string(),
+        })
+        .await;
+    match res {
+        Ok(_) => Ok(true),
+        Err(err) if is_ordered_not_found(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+struct Planner {
+    report: DataStoreErasureReport,
+    seen: HashSet<(ErasureSource, String, String, String)>,
+}
+
+impl Planner {
+    fn push(
+        &mut self,
+        source: ErasureSource,
+        name: &str,
+        scope: &str,
+        key: &str,
+        matched_by: ErasureMatch,
+        version: Option<String>,
+    ) {
+        let id = (source, name.to_string(), scope.to_string(), key.to_string());
+        if !self.seen.insert(id) {
+            return;
+        }
+        self.report.entries.push(ErasedEntry {
+            source,
+            name: name.to_string(),
+            scope: scope.to_string(),
+            key: key.to_string(),
+            matched_by,
+            version,
+            status: ErasureStatus::Pending,
+            error: None,
+        });
+    }
+}
+
+async fn scan_datastore(
+    datastore: &RbxDataStore,
+    name: &str,
+    params: &DataStoreErasure,
+    planner: &mut Planner,
+) -> Result<(), Error> {
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: name.to_string(),
+        scope: None,
+        all_scopes: true,
+        prefix: None,
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    let shared = Arc::new((datastore.clone(), name.to_string(), params.user_id));
+    while let Some(keys) = pager.next_page().await? {
+        planner.report.checked += keys.len() as u64;
+        let found = map_concurrent(keys, params.concurrency, |key| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name, user_id) = shared.as_ref();
+                let res = find_entry(datastore, name, &key, Some(*user_id)).await;
+                (key, res)
+            }
+        })
+        .await;
+        for (key, res) in found {
+            match res {
+                Ok(Some(version)) => planner.push(
+                    ErasureSource::DataStore,
+                    name,
+                    &key.scope,
+                    &key.key,
+                    ErasureMatch::UserIdMetadata,
+                    version,
+                ),
+                Ok(None) => {}
+                Err(err) => planner
+                    .report
+                    .errors
+                    .push(format!("{name}/{}/{}: {err}", key.scope, key.key)),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find every entry associated with the user. Nothing is deleted.
+pub async fn plan_erasure(
+    datastore: &RbxDataStore,
+    params: &DataStoreErasure,
+) -> Result<DataStoreErasureReport, Error> {
+    let ordered = RbxOrderedDataStore {
+        api_key: datastore.api_key.clone(),
+        universe_id: datastore.universe_id,
+    };
+    let mut planner = Planner {
+        report: DataStoreErasureReport {
+            user_id: params.user_id,
+            universe_id: datastore.universe_id.0,
+            planned_time: now(),
+            applied_time: None,
+            checked: 0,
+            entries: vec![],
+            errors: vec![],
+        },
+        seen: HashSet::new(),
+    };
+
+    for template in &params.templates {
+        let key = ListEntriesKey {
+            scope: template
+                .scope
+                .clone()
+                .unwrap_or_else(|| "global".to_string()),
+            key: template.render(params.user_id),
+        };
+        planner.report.checked += 1;
+        match find_entry(datastore, &template.name, &key, None).await {
+            Ok(Some(version)) => planner.push(
+                ErasureSource::DataStore,
+                &template.name,
+                &key.scope,
+                &key.key,
+                ErasureMatch::KeyTemplate,
+                version,
+            ),
+            Ok(None) => {}
+            Err(err) => planner.report.errors.push(format!(
+                "{}/{}/{}: {err}",
+                template.name, key.scope, key.key
+            )),
+        }
+    }
+
+    for template in &params.ordered_templates {
+        let id = template.render(params.user_id);
+        let scope = template.scope.as_deref().unwrap_or("global");
+        planner.report.checked += 1;
+        match find_ordered_entry(&ordered, template, &id).await {
+            Ok(true) => planner.push(
+                ErasureSource::OrderedDataStore,
+                &template.name,
+                scope,
+                &id,
+                ErasureMatch::KeyTemplate,
+                None,
+            ),
+            Ok(false) => {}
+            Err(err) => planner
+                .report
+                .errors
+                .push(format!("{}/{scope}/{id}: {err}", template.name)),
+        }
+    }
+
+    if params.scan {
+        let names = match &params.scan_datastores {
+            Some(names) => names.clone(),
+            None => datastore.list_all_store_names(None).await?,
+        };
+        for name in names {
+            scan_datastore(datastore, &name, params, &mut planner).await?;
+        }
+    }
+
+    let user_id = params.user_id.to_string();
+    for target in &params.scan_ordered_datastores {
+        let scope = target.scope.as_deref().unwrap_or("global");
+        let mut page_token = None;
+        loop {
+            let res = ordered
+                .list_entries(&OrderedDataStoreListEntries {
+                    name: target.name.clone(),
+                    scope: target.scope.clone(),
+                    max_page_size: Some(PageSize(100)),
+                    page_token,
+                    order_by: None,
+                    filter: None,
+                })
+                .await?;
+            planner.report.checked += res.entries.len() as u64;
+            for entry in res.entries.iter().filter(|entry| entry.id == user_id) {
+                planner.push(
+                    ErasureSource::OrderedDataStore,
+                    &target.name,
+                    scope,
+                    &entry.id,
+                    ErasureMatch::EntryId,
+                    None,
+                );
+            }
+            page_token = res.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(planner.report)
+}
+
+async fn erase_entry(
+    datastore: &RbxDataStore,
+    ordered: &RbxOrderedDataStore,
+    entry: &ErasedEntry,
+) -> Result<ErasureStatus, Error> {
+    match entry.source {
+        ErasureSource::DataStore => {
+            let res = datastore
+                .delete_entry(&DataStoreDeleteEntry {
+                    name: entry.name.clone(),
+                    scope: Some(entry.scope.clone()),
+                    key: entry.key.clone(),
+                })
+                .await;
+            match res {
+                Ok(()) => Ok(ErasureStatus::Deleted),
+                Err(err) if is_entry_not_found(&err) => Ok(ErasureStatus::Missing),
+                Err(err) => Err(err),
+            }
+        }
+        ErasureSource::OrderedDataStore => {
+            let res = ordered
+                .delete_entry(&OrderedDataStoreEntry {
+                    name: entry.name.clone(),
+                    scope: Some(entry.scope.clone()),
+                    id: entry.key.clone(),
+                })
+                .await;
+            match res {
+                Ok(()) => Ok(ErasureStatus::Deleted),
+                Err(err) if is_ordered_not_found(&err) => Ok(ErasureStatus::Missing),
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
+
+/// Delete the entries found by `plan_erasure`.
+pub async fn apply_erasure(
+    datastore: &RbxDataStore,
+    plan: &DataStoreErasureReport,
+    concurrency: usize,
+) -> Result<DataStoreErasureReport, Error> {
+    let ordered = RbxOrderedDataStore {
+        api_key: datastore.api_key.clone(),
+        universe_id: datastore.universe_id,
+    };
+    let shared = Arc::new((datastore.clone(), ordered));
+    let entries = map_concurrent(plan.entries.clone(), concurrency, |mut entry| {
+        let shared = shared.clone();
+        async move {
+            let (datastore, ordered) = shared.as_ref();
+            match erase_entry(datastore, ordered, &entry).await {
+                Ok(status) => entry.status = status,
+                Err(err) => {
+                    entry.status = ErasureStatus::Failed;
+                    entry.error = Some(err.to_string());
+                }
+            }
+            entry
+        }
+    })
+    .await;
+    Ok(DataStoreErasureReport {
+        applied_time: Some(now()),
+        entries,
+        ..plan.clone()
+    })
+}
@@ -0,0 +1,146 @@
+//! Searching DataStore values with a filter expression.
+//!
+//! `query` lists the keys of a DataStore, fetches their values concurrently,
+//! and sends every entry whose value matches the filter through a channel.
+//! Filters and projections are `rbx::expr` expressions, e.g.
+//! `.coins > 1e9 and (.banned | not)` and `{coins, level: .stats.level}`.
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::rbx::{error::Error, expr::Expression, util::map_concurrent};
+
+use super::{
+    datastore_dump::{fetch_entry, DumpedEntry},
+    DataStoreListEntries, RbxDataStore, ReturnLimit,
+};
+
+pub struct DataStoreQuery {
+    pub name: String,
+    pub scope: Option<String>,
+    pub all_scopes: bool,
+    pub prefix: Option<String>,
+    /// Only entries whose value matches this predicate are returned. Every
+    /// entry matches when there is no filter.
+    pub filter: Option<Expression>,
+    /// Return the result of this expression instead of the whole value.
+    pub select: Option<Expression>,
+    /// Maximum number of values fetched at once.
+    pub concurrency: usize,
+    /// Stop after fetching this many values.
+    pub max_reads: Option<u64>,
+    /// Stop after this many matches.
+    pub limit: Option<u64>,
+}
+
+/// An entry matching a query.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryMatch {
+    pub scope: String,
+    pub key: String,
+    /// The value, or its projection when the query has a `select`.
+    pub value: Value,
+}
+
+/// Evaluate a query's filter and projection against one entry. Returns
+/// `None` if the entry does not match.
+pub fn query_entry(
+    entry: DumpedEntry,
+    filter: Option<&Expression>,
+    select: Option<&Expression>,
+) -> Result<Option<QueryMatch>, Error> {
+    if let Some(filter) = filter {
+        if !filter.matches(&entry.value)? {
+            return Ok(None);
+        }
+    }
+    let value = match select {
+        Some(select) => select.evaluate(&entry.value)?,
+        None => entry.value,
+    };
+    Ok(Some(QueryMatch {
+        scope: entry.scope,
+        key: entry.key,
+        value,
+    }))
+}
+
+async fn run_query(
+    datastore: RbxDataStore,
+    params: DataStoreQuery,
+    tx: mpsc::Sender<Result<QueryMatch, Error>>,
+) {
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: params.name.clone(),
+        scope: params.scope.clone(),
+        all_scopes: params.all_scopes,
+        prefix: params.prefix.clone(),
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    let shared = Arc::new((datastore, params.name.clone()));
+    let mut reads = 0;
+    let mut matches = 0;
+    loop {
+        let mut keys = match pager.next_page().await {
+            Ok(Some(keys)) => keys,
+            Ok(None) => return,
+            Err(err) => {
+                let _ = tx.send(Err(err)).await;
+                return;
+            }
+        };
+        if let Some(max_reads) = params.max_reads {
+            keys.truncate(max_reads.saturating_sub(reads) as usize);
+        }
+        reads += keys.len() as u64;
+        let results = map_concurrent(keys, params.concurrency, |key| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name) = shared.as_ref();
+                fetch_entry(datastore, name, &key).await
+            }
+        })
+        .await;
+        for result in results {
+            let result = result.and_then(|entry| {
+                query_entry(entry, params.filter.as_ref(), params.select.as_ref())
+            });
+            let item = match result {
+                Ok(Some(found)) => {
+                    matches += 1;
+                    Ok(found)
+                }
+                Ok(None) => continue,
+                Err(err) => Err(err),
+            };
+            if tx.send(item).await.is_err() {
+                return;
+            }
+            if params.limit.is_some_and(|limit| matches >= limit) {
+                return;
+            }
+        }
+        if params.max_reads.is_some_and(|max_reads| reads >= max_reads) {
+            return;
+        }
+    }
+}
+
+/// Start a query. Matches are sent in key-listing order until every key has
+/// been read, a budget is exhausted, or the receiver is dropped.
+///
+/// Errors for single entries (e.g. values that are not JSON) are sent
+/// through the channel and the query continues; a failure to list keys ends
+/// it.
+pub fn query(
+    datastore: &RbxDataStore,
+    params: DataStoreQuery,
+) -> mpsc::Receiver<Result<QueryMatch, Error>> {
+    let (tx, rx) = mpsc::channel(params.concurrency.max(1) * 2);
+    tokio::spawn(run_query(datastore.clone(), params, tx));
+    rx
+}
@@ -0,0 +1,355 @@
+//! Point-in-time restore of DataStore entries from their version history.
+//!
+//! A restore happens in two steps. `plan_restore` finds, for every matching
+//! key, the latest version at or before the requested time and compares it
+//! with the current value, without writing anything. `apply_restore` then
+//! writes the planned versions back as the current values.
+//!
+//! With `RestoreKeys::Prefix` and `RestoreKeys::All`, keys are found by
+//! listing the DataStore, which only returns keys that currently exist. Keys
+//! deleted after the requested time are not restored; restore them one at a
+//! time with `RestoreKeys::Key`.
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    datastore::{is_entry_not_found, EntryAttributes, ListEntriesKey},
+    DataStoreDeleteEntry, DataStoreGetEntry, DataStoreGetEntryVersion, DataStoreListEntries,
+    DataStoreListEntryVersions, DataStoreSetEntry, RbxDataStore, ReturnLimit, RobloxUserId,
+};
+
+/// Which keys of a DataStore to restore.
+#[derive(Debug, Clone)]
+pub enum RestoreKeys {
+    /// A single key.
+    Key(String),
+    /// Every existing key starting with the prefix.
+    Prefix(String),
+    /// Every existing key in the DataStore.
+    All,
+}
+
+pub struct DataStoreRestoreEntries {
+    pub name: String,
+    pub scope: Option<String>,
+    /// Restore keys from all scopes. Ignored when restoring a single key.
+    pub all_scopes: bool,
+    pub keys: RestoreKeys,
+    /// Restore each key to the latest version at or before this time.
+    pub at: DateTime<Utc>,
+    /// Maximum number of keys processed at once.
+    pub concurrency: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreAction {
+    /// Write the target version back as the current value.
+    Restore,
+    /// The key was deleted at the target time, so the current value is deleted.
+    Delete,
+    /// The current value already matches the target version.
+    Unchanged,
+    /// The key had no version at or before the target time.
+    NoVersion,
+    /// Planning or applying the restore failed.
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreEntry {
+    pub scope: String,
+    pub key: String,
+    pub action: RestoreAction,
+    pub target_version: Option<String>,
+    pub target_version_time: Option<String>,
+    pub current_version: Option<String>,
+    pub previous_value: Option<String>,
+    pub restored_value: Option<String>,
+    pub restored_user_ids: Vec<RobloxUserId>,
+    pub restored_attributes: Option<EntryAttributes>,
+    pub applied: bool,
+    pub new_version: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreRestoreReport {
+    pub name: String,
+    pub at: String,
+    pub entries: Vec<RestoreEntry>,
+}
+
+impl DataStoreRestoreReport {
+    /// Number of entries with the given action.
+    pub fn count(&self, action: RestoreAction) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+
+    /// Entries that will be (or were) written or deleted.
+    pub fn changes(&self) -> impl Iterator<Item = &RestoreEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.action, RestoreAction::Restore | RestoreAction::Delete))
+    }
+}
+
+async fn plan_entry(
+    datastore: &RbxDataStore,
+    name: &str,
+    at: &str,
+    key: &ListEntriesKey,
+    entry: &mut RestoreEntry,
+) -> Result<(), Error> {
+    let versions = datastore
+        .list_entry_versions(&DataStoreListEntryVersions {
+            name: name.to_string(),
+            scope: Some(key.scope.clone()),
+            key: key.key.clone(),
+            start_time: None,
+            end_time: Some(at.to_string()),
+            sort_order: "Descending".to_string(),
+            limit: ReturnLimit(1),
+            cursor: None,
+        })
+        .await?;
+
+    let current = match datastore
+        .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+            name: name.to_string(),
+            scope: Some(key.scope.clone()),
+            key: key.key.clone(),
+        })
+        .await
+    {
+        Ok(current) => Some(current),
+        Err(err) if is_entry_not_found(&err) => None,
+        Err(err) => return Err(err),
+    };
+    if let Some(current) = &current {
+        entry.current_version = current.metadata.version.clone();
+        entry.previous_value = Some(current.data.clone());
+    }
+
+    let Some(target) = versions.versions.into_iter().next() else {
+        entry.action = RestoreAction::NoVersion;
+        return Ok(());
+    };
+    entry.target_version = Some(target.version.clone());
+    entry.target_version_time = Some(target.created_time.clone());
+
+    if target.deleted {
+        entry.action = match current {
+            Some(_) => RestoreAction::Delete,
+            None => RestoreAction::Unchanged,
+        };
+        return Ok(());
+    }
+
+    let restored = datastore
+        .get_entry_version_with_metadata(&DataStoreGetEntryVersion {
+            name: name.to_string(),
+            scope: Some(key.scope.clone()),
+            key: key.key.clone(),
+            version_id: target.version.clone(),
+        })
+        .await?;
+    // Metadata is restored along with the value, so a key only counts as
+    // unchanged when its user IDs and attributes match too.
+    let unchanged = current.as_ref().is_some_and(|current| {
+        current.metadata.version.as_deref() == Some(target.version.as_str())
+            || (current.data == restored.data
+                && current.metadata.user_ids == restored.metadata.user_ids
+                && current.metadata.attributes.clone().unwrap_or_default()
+                    == restored.metadata.attributes.clone().unwrap_or_default())
+    });
+    entry.action = if unchanged {
+        RestoreAction::Unchanged
+    } else {
+        RestoreAction::Restore
+    };
+    entry.restored_value = Some(restored.data);
+    entry.restored_user_ids = restored.metadata.user_ids;
+    entry.restored_attributes = restored.metadata.attributes;
+    Ok(())
+}
+
+async fn apply_entry(
+    datastore: &RbxDataStore,
+    name: &str,
+    entry: &mut RestoreEntry,
+) -> Result<(), Error> {
+    match entry.action {
+        RestoreAction::Restore => {
+            let user_ids = entry.restored_user_ids.clone();
+            let res = datastore
+                .set_entry(&DataStoreSetEntry {
+                    name: name.to_string(),
+                    scope: Some(entry.scope.clone()),
+                    key: entry.key.clone(),
+                    match_version: entry.current_version.clone(),
+                    exclusive_create: entry.current_version.is_none().then_some(true),
+                    roblox_entry_user_ids: (!user_ids.is_empty()).then_some(user_ids),
+                    roblox_entry_attributes: entry.restored_attributes.clone(),
+                    data: entry.restored_value.clone().unwrap_or_default(),
+                })
+                .await?;
+            entry.new_version = Some(res.version);
+            entry.applied = true;
+        }
+        RestoreAction::Delete => {
+            // Deletes cannot be guarded by matchVersion, so the key is read
+            // again and only deleted if it is still at the planned version.
+            let latest = match datastore
+                .get_entry_with_metadata_uncached(&DataStoreGetEntry {
+                    name: name.to_string(),
+                    scope: Some(entry.scope.clone()),
+                    key: entry.key.clone(),
+                })
+                .await
+            {
+                Ok(current) => current.metadata.version,
+                Err(err) if is_entry_not_found(&err) => None,
+                Err(err) => return Err(err),
+            };
+            match latest {
+                None => {}
+                Some(version) if entry.current_version.as_ref() == Some(&version) => {
+                    datastore
+                        .delete_entry(&DataStoreDeleteEntry {
+                            name: name.to_string(),
+                            scope: Some(entry.scope.clone()),
+                            key: entry.key.clone(),
+                        })
+                        .await?;
+                }
+                Some(_) => {
+                    entry.action = RestoreAction::Failed;
+                    entry.error = Some("modified since planning".to_string());
+                    return Ok(());
+                }
+            }
+            entry.applied = true;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Find the version each matching key had at the requested time, and compare
+/// it against the current value. Nothing is written.
+///
+/// Keys that no longer exist are only planned when given with
+/// `RestoreKeys::Key`.
+pub async fn plan_restore(
+    datastore: &RbxDataStore,
+    params: &DataStoreRestoreEntries,
+) -> Result<DataStoreRestoreReport, Error> {
+    let at = params.at.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+    let mut report = DataStoreRestoreReport {
+        name: params.name.clone(),
+        at: at.clone(),
+        entries: vec![],
+    };
+
+    let (prefix, all_scopes) = match &params.keys {
+        RestoreKeys::Key(key) => {
+            let keys = vec![ListEntriesKey {
+                scope: params.scope.clone().unwrap_or_else(|| "global".to_string()),
+                key: key.clone(),
+            }];
+            report.entries = plan_keys(datastore, params, &at, keys).await;
+            return Ok(report);
+        }
+        RestoreKeys::Prefix(prefix) => (Some(prefix.clone()), params.all_scopes),
+        RestoreKeys::All => (None, params.all_scopes),
+    };
+
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: params.name.clone(),
+        scope: params.scope.clone(),
+        all_scopes,
+        prefix,
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    while let Some(keys) = pager.next_page().await? {
+        let entries = plan_keys(datastore, params, &at, keys).await;
+        report.entries.extend(entries);
+    }
+    Ok(report)
+}
+
+async fn plan_keys(
+    datastore: &RbxDataStore,
+    params: &DataStoreRestoreEntries,
+    at: &str,
+    keys: Vec<ListEntriesKey>,
+) -> Vec<RestoreEntry> {
+    let shared = Arc::new((datastore.clone(), params.name.clone(), at.to_string()));
+    map_concurrent(keys, params.concurrency, |key| {
+        let shared = shared.clone();
+        async move {
+            let (datastore, name, at) = shared.as_ref();
+            let mut entry = RestoreEntry {
+                scope: key.scope.clone(),
+                key: key.key.clone(),
+                action: RestoreAction::Failed,
+                target_version: None,
+                target_version_time: None,
+                current_version: None,
+                previous_value: None,
+                restored_value: None,
+                restored_user_ids: vec![],
+                restored_attributes: None,
+                applied: false,
+                new_version: None,
+                error: None,
+            };
+            if let Err(err) = plan_entry(datastore, name, at, &key, &mut entry).await {
+                entry.action = RestoreAction::Failed;
+                entry.error = Some(err.to_string());
+            }
+            entry
+        }
+    })
+    .await
+}
+
+/// Apply a plan produced by `plan_restore`.
+///
+/// Writes use the planned current version as `matchVersion`, so keys that
+/// were modified after planning are reported as failures rather than
+/// overwritten. Deletes have no such guard: the key is read again right
+/// before it is deleted, and skipped as a failure if its version changed.
+/// A write landing between that read and the delete is still lost.
+pub async fn apply_restore(
+    datastore: &RbxDataStore,
+    plan: &DataStoreRestoreReport,
+    concurrency: usize,
+) -> Result<DataStoreRestoreReport, Error> {
+    let shared = Arc::new((datastore.clone(), plan.name.clone()));
+    let entries = map_concurrent(plan.entries.clone(), concurrency, |mut entry| {
+        let shared = shared.clone();
+        async move {
+            let (datastore, name) = shared.as_ref();
+            if let Err(err) = apply_entry(datastore, name, &mut entry).await {
+                entry.action = RestoreAction::Failed;
+                entry.error = Some(err.to_string());
+            }
+            entry
+        }
+    })
+    .await;
+    Ok(DataStoreRestoreReport {
+        name: plan.name.clone(),
+        at: plan.at.clone(),
+        entries,
+    })
+}
@@ -0,0 +1,126 @@
+//! JSON Schema validation of DataStore values.
+//!
+//! Schemas are registered on an `RbxDataStore` for a DataStore name and key
+//! pattern (`*` and `?` wildcards). Every value written through
+//! `RbxDataStore::set_entry` (including the writes done by copy, import, and
+//! restore) is validated against all matching schemas before it is sent.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rbx::{
+    error::Error,
+    json_schema::{JsonSchema, SchemaViolation},
+    util::glob_match,
+};
+
+use super::{
+    datastore_dump::{for_each_entry_page, DataStoreExportEntries, EntryReadError},
+    RbxDataStore,
+};
+
+/// A schema registered for a set of DataStore keys.
+#[derive(Debug, Clone)]
+pub struct DataStoreSchema {
+    /// DataStore name pattern.
+    pub name: String,
+    /// Key pattern.
+    pub key: String,
+    pub schema: JsonSchema,
+}
+
+impl DataStoreSchema {
+    pub fn matches(&self, name: &str, key: &str) -> bool {
+        glob_match(&self.name, name) && glob_match(&self.key, key)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidEntry {
+    pub scope: String,
+    pub key: String,
+    pub violations: Vec<SchemaViolation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreValidationReport {
+    pub valid: u64,
+    pub invalid: u64,
+    /// Entries without a matching schema.
+    pub unchecked: u64,
+    /// Entries that could not be read, e.g. because their value is not JSON.
+    pub unreadable: u64,
+    pub entries: Vec<InvalidEntry>,
+    pub errors: Vec<EntryReadError>,
+}
+
+/// Validate a value against every matching schema. Values that are not valid
+/// JSON are rejected when a schema matches.
+pub(crate) fn check_value(
+    schemas: &[DataStoreSchema],
+    name: &str,
+    key: &str,
+    data: &str,
+) -> Result<(), Error> {
+    let mut matching = schemas.iter().filter(|s| s.matches(name, key)).peekable();
+    if matching.peek().is_none() {
+        return Ok(());
+    }
+    let violations: Vec<SchemaViolation> = match serde_json::from_str::<Value>(data) {
+        Ok(value) => matching
+            .flat_map(|schema| schema.schema.validate(&value))
+            .collect(),
+        Err(err) => vec![SchemaViolation {
+            path: String::new(),
+            message: format!("value is not valid JSON: {err}"),
+        }],
+    };
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SchemaValidationError(violations))
+    }
+}
+
+/// Validate the existing entries of a DataStore against the registered
+/// schemas. Entries that cannot be read are counted as unreadable and
+/// validation continues.
+pub async fn validate_entries(
+    datastore: &RbxDataStore,
+    params: &DataStoreExportEntries,
+) -> Result<DataStoreValidationReport, Error> {
+    let schemas = &datastore.schemas;
+    let mut report = DataStoreValidationReport::default();
+    let errors = for_each_entry_page(datastore, params, |page| {
+        for entry in page {
+            let matching: Vec<&DataStoreSchema> = schemas
+                .iter()
+                .filter(|s| s.matches(&params.name, &entry.key))
+                .collect();
+            if matching.is_empty() {
+                report.unchecked += 1;
+                continue;
+            }
+            let violations: Vec<SchemaViolation> = matching
+                .iter()
+                .flat_map(|schema| schema.schema.validate(&entry.value))
+                .collect();
+            if violations.is_empty() {
+                report.valid += 1;
+            } else {
+                report.invalid += 1;
+                report.entries.push(InvalidEntry {
+                    scope: entry.scope,
+                    key: entry.key,
+                    violations,
+                });
+            }
+        }
+        Ok(())
+    })
+    .await?;
+    report.unreadable = errors.len() as u64;
+    report.errors = errors;
+    Ok(report)
+}
@@ -0,0 +1,122 @@
+//! Triggering DataStore snapshots and keeping track of them.
+//!
+//! Roblox only reports the time of the latest snapshot of a universe, so every
+//! snapshot taken through `take_snapshot` can be appended to a snapshot log
+//! (NDJSON, one `SnapshotRecord` per line). The log is then used to find the
+//! snapshot taken before a given entry version, which is the point in time
+//! to pass to `plan_restore`.
+use std::io::{BufRead, Write};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{
+    error::Error,
+    v2::datastore::{snapshot_data_stores, SnapshotDataStoresParams},
+};
+
+use super::{DataStoreGetEntryVersion, RbxDataStore};
+
+/// A snapshot of all DataStores in a universe.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRecord {
+    /// Time of the snapshot, as reported by Roblox. `None` if no time was
+    /// reported, in which case the snapshot cannot be used to restore.
+    #[serde(default)]
+    pub snapshot_time: Option<DateTime<Utc>>,
+    /// `false` if the request reused a snapshot taken earlier the same UTC day.
+    pub new_snapshot_taken: bool,
+    /// When the snapshot was requested.
+    pub requested_time: DateTime<Utc>,
+    /// Free-form note, e.g. the deploy the snapshot was taken for.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>, Error> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|err| Error::InvalidTime(format!("'{time}': {err}")))
+}
+
+/// Take a snapshot of every DataStore in the universe. At most one snapshot
+/// is taken per UTC day; later requests report the existing one.
+pub async fn take_snapshot(
+    datastore: &RbxDataStore,
+    label: Option<String>,
+) -> Result<SnapshotRecord, Error> {
+    let requested_time = Utc::now();
+    let res = snapshot_data_stores(&SnapshotDataStoresParams {
+        api_key: datastore.api_key.clone(),
+        universe_id: datastore.universe_id,
+    })
+    .await?;
+    let snapshot_time = match &res.latest_snapshot_time {
+        Some(time) => Some(parse_time(time)?),
+        None => None,
+    };
+    Ok(SnapshotRecord {
+        snapshot_time,
+        new_snapshot_taken: res.new_snapshot_taken,
+        requested_time,
+        label,
+    })
+}
+
+/// Append a snapshot to a snapshot log.
+pub fn write_snapshot_record<W: Write>(
+    writer: &mut W,
+    record: &SnapshotRecord,
+) -> Result<(), Error> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Read a snapshot log, sorted by snapshot time.
+pub fn read_snapshot_log<R: BufRead>(reader: R) -> Result<Vec<SnapshotRecord>, Error> {
+    let mut records = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push(serde_json::from_str::<SnapshotRecord>(&line)?);
+    }
+    records.sort_by_key(|r| r.snapshot_time);
+    Ok(records)
+}
+
+/// The latest snapshot taken strictly before `time`. Snapshots without a
+/// reported time are ignored.
+pub fn snapshot_before(records: &[SnapshotRecord], time: DateTime<Utc>) -> Option<&SnapshotRecord> {
+    records
+        .iter()
+        .filter(|r| r.snapshot_time.is_some_and(|t| t < time))
+        .max_by_key(|r| r.snapshot_time)
+}
+
+/// The latest snapshot taken before the given version of an entry was
+/// written.
+pub async fn snapshot_before_version<'a>(
+    datastore: &RbxDataStore,
+    records: &'a [SnapshotRecord],
+    params: &DataStoreGetEntryVersion,
+) -> Result<&'a SnapshotRecord, Error> {
+    let version = datastore.get_entry_version_with_metadata(params).await?;
+    let Some(created) = version.metadata.version_created_time else {
+        return Err(Error::InvalidTime(format!(
+            "version {} has no creation time",
+            params.version_id
+        )));
+    };
+    let created = parse_time(&created)?;
+    snapshot_before(records, created).ok_or_else(|| {
+        Error::SnapshotNotFound(format!(
+            "no snapshot before version {} ({})",
+            params.version_id,
+            created.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ))
+    })
+}
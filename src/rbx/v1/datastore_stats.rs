@@ -0,0 +1,243 @@
+//! Storage usage reports for DataStores.
+//!
+//! Sizes come from the `contentLength` of each key's latest version, so
+//! values are never downloaded. Counting versions pages through each key's
+//! whole history and costs one more request per 100 versions.
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    datastore::{ListEntriesKey, MAX_VALUE_SIZE},
+    DataStoreListEntries, DataStoreListEntryVersions, RbxDataStore, ReturnLimit,
+};
+
+pub struct DataStoreStatsParams {
+    /// DataStores to report on. Every DataStore is reported when empty.
+    pub names: Vec<String>,
+    /// Only report on DataStores with this name prefix.
+    pub name_prefix: Option<String>,
+    pub scope: Option<String>,
+    pub all_scopes: bool,
+    /// Only count keys with this prefix.
+    pub prefix: Option<String>,
+    /// Number of largest keys to report per DataStore.
+    pub top: usize,
+    /// Flag keys using at least this fraction of the 4MB value limit.
+    pub flag_threshold: f64,
+    /// Also count the versions of every key.
+    pub count_versions: bool,
+    /// Maximum number of keys processed at once.
+    pub concurrency: usize,
+}
+
+/// Number of values in a size range.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeBucket {
+    /// Human-readable range, e.g. `1KB-10KB`.
+    pub range: String,
+    /// Exclusive upper bound in bytes (`None` for the last bucket).
+    pub max_size: Option<u64>,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStats {
+    pub scope: String,
+    pub key: String,
+    pub size: u64,
+    /// Fraction of the 4MB value limit used.
+    pub limit_usage: f64,
+    pub versions: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreStats {
+    pub name: String,
+    pub key_count: u64,
+    pub total_size: u64,
+    pub max_size: u64,
+    /// Total number of versions, when versions are counted.
+    pub version_count: Option<u64>,
+    /// Key count per scope.
+    pub scopes: BTreeMap<String, u64>,
+    pub size_distribution: Vec<SizeBucket>,
+    pub largest_keys: Vec<KeyStats>,
+    /// Keys at or above the flag threshold.
+    pub flagged_keys: Vec<KeyStats>,
+    /// Keys whose size could not be read.
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreStatsReport {
+    pub universe_id: u64,
+    pub generated_time: String,
+    pub datastores: Vec<DataStoreStats>,
+}
+
+const BUCKETS: [(&str, Option<u64>); 5] = [
+    ("<1KB", Some(1024)),
+    ("1KB-10KB", Some(10 * 1024)),
+    ("10KB-100KB", Some(100 * 1024)),
+    ("100KB-1MB", Some(1024 * 1024)),
+    (">=1MB", None),
+];
+
+fn empty_buckets() -> Vec<SizeBucket> {
+    BUCKETS
+        .iter()
+        .map(|(range, max_size)| SizeBucket {
+            range: range.to_string(),
+            max_size: *max_size,
+            count: 0,
+        })
+        .collect()
+}
+
+/// Size of the latest version of a key, and optionally its version count.
+async fn key_stats(
+    datastore: &RbxDataStore,
+    name: &str,
+    key: &ListEntriesKey,
+    count_versions: bool,
+) -> Result<KeyStats, Error> {
+    let mut params = DataStoreListEntryVersions {
+        name: name.to_string(),
+        scope: Some(key.scope.clone()),
+        key: key.key.clone(),
+        start_time: None,
+        end_time: None,
+        sort_order: "Descending".to_string(),
+        limit: ReturnLimit(if count_versions { 100 } else { 1 }),
+        cursor: None,
+    };
+    let res = datastore.list_entry_versions(&params).await?;
+    let size = res.versions.first().map_or(0, |v| v.content_length);
+    let mut versions = None;
+    if count_versions {
+        let mut count = res.versions.len() as u64;
+        params.cursor = res.next_page_cursor.filter(|c| !c.is_empty());
+        while params.cursor.is_some() {
+            let res = datastore.list_entry_versions(&params).await?;
+            count += res.versions.len() as u64;
+            params.cursor = res.next_page_cursor.filter(|c| !c.is_empty());
+        }
+        versions = Some(count);
+    }
+    Ok(KeyStats {
+        scope: key.scope.clone(),
+        key: key.key.clone(),
+        size,
+        limit_usage: size as f64 / MAX_VALUE_SIZE as f64,
+        versions,
+    })
+}
+
+async fn datastore_stats(
+    datastore: &RbxDataStore,
+    name: &str,
+    params: &DataStoreStatsParams,
+) -> Result<DataStoreStats, Error> {
+    let mut stats = DataStoreStats {
+        name: name.to_string(),
+        key_count: 0,
+        total_size: 0,
+        max_size: 0,
+        version_count: params.count_versions.then_some(0),
+        scopes: BTreeMap::new(),
+        size_distribution: empty_buckets(),
+        largest_keys: vec![],
+        flagged_keys: vec![],
+        errors: vec![],
+    };
+    let mut pager = datastore.key_pager(DataStoreListEntries {
+        name: name.to_string(),
+        scope: params.scope.clone(),
+        all_scopes: params.all_scopes,
+        prefix: params.prefix.clone(),
+        limit: ReturnLimit(100),
+        cursor: None,
+    });
+    let shared = Arc::new((datastore.clone(), name.to_string()));
+    while let Some(keys) = pager.next_page().await? {
+        let count_versions = params.count_versions;
+        let results = map_concurrent(keys, params.concurrency, |key| {
+            let shared = shared.clone();
+            async move {
+                let (datastore, name) = shared.as_ref();
+                key_stats(datastore, name, &key, count_versions)
+                    .await
+                    .map_err(|err| format!("{}/{}: {err}", key.scope, key.key))
+            }
+        })
+        .await;
+        for result in results {
+            let key = match result {
+                Ok(key) => key,
+                Err(err) => {
+                    stats.errors.push(err);
+                    continue;
+                }
+            };
+            stats.key_count += 1;
+            stats.total_size += key.size;
+            stats.max_size = stats.max_size.max(key.size);
+            *stats.scopes.entry(key.scope.clone()).or_default() += 1;
+            if let (Some(total), Some(versions)) = (&mut stats.version_count, key.versions) {
+                *total += versions;
+            }
+            if let Some(bucket) = stats
+                .size_distribution
+                .iter_mut()
+                .find(|b| b.max_size.is_none_or(|max| key.size < max))
+            {
+                bucket.count += 1;
+            }
+            if key.limit_usage >= params.flag_threshold {
+                stats.flagged_keys.push(key.clone());
+            }
+            stats.largest_keys.push(key);
+            if stats.largest_keys.len() > params.top * 2 {
+                stats.largest_keys.sort_by_key(|k| Reverse(k.size));
+                stats.largest_keys.truncate(params.top);
+            }
+        }
+    }
+    stats.largest_keys.sort_by_key(|k| Reverse(k.size));
+    stats.largest_keys.truncate(params.top);
+    stats.flagged_keys.sort_by_key(|k| Reverse(k.size));
+    Ok(stats)
+}
+
+/// Report key counts, sizes, and optionally version counts for DataStores.
+pub async fn stats(
+    datastore: &RbxDataStore,
+    params: &DataStoreStatsParams,
+) -> Result<DataStoreStatsReport, Error> {
+    let names = if params.names.is_empty() {
+        datastore
+            .list_all_store_names(params.name_prefix.clone())
+            .await?
+    } else {
+        params.names.clone()
+    };
+    let mut report = DataStoreStatsReport {
+        universe_id: datastore.universe_id.0,
+        generated_time: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        datastores: vec![],
+    };
+    for name in names {
+        report
+            .datastores
+            .push(datastore_stats(datastore, &name, params).await?);
+    }
+    Ok(report)
+}
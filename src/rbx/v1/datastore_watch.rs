@@ -0,0 +1,328 @@
+//! Watching DataStore keys for changes.
+//!
+//! `watch` polls the version history of the watched keys and sends an event
+//! for every new version through a channel. The first poll only records the
+//! current version of each key, and is retried at the next polls for keys
+//! where that failed; events are sent for versions written after that.
+//!
+//! With `WatchKeys::Prefix`, keys are found by listing the DataStore, which
+//! leaves deleted keys out. A watched key that is no longer listed is polled
+//! one last time, so its deletion is still sent, and is then forgotten.
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    sync::mpsc,
+    time::{sleep_until, Instant},
+};
+
+use crate::rbx::{
+    error::Error,
+    json_diff::{diff, JsonChange},
+    util::Throttle,
+};
+
+use super::{
+    datastore::{ListEntriesKey, ListEntryVersion},
+    DataStoreGetEntryVersion, DataStoreListEntries, DataStoreListEntryVersions, RbxDataStore,
+    ReturnLimit,
+};
+
+/// Which keys of a DataStore to watch.
+#[derive(Debug, Clone)]
+pub enum WatchKeys {
+    /// The given keys.
+    Keys(Vec<String>),
+    /// Every key starting with the prefix, including keys created while
+    /// watching.
+    Prefix(String),
+}
+
+pub struct DataStoreWatch {
+    pub name: String,
+    pub scope: Option<String>,
+    pub keys: WatchKeys,
+    /// Time between two polls of the same key.
+    pub poll_interval: Duration,
+    /// Maximum number of requests sent per minute (0 for no limit).
+    pub max_requests_per_minute: u32,
+}
+
+/// A new version of a watched key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreWatchEvent {
+    pub scope: String,
+    pub key: String,
+    pub version: String,
+    pub previous_version: Option<String>,
+    pub created_time: String,
+    /// The version is a deletion of the key.
+    pub deleted: bool,
+    /// The previous version could not be found in the version history (for
+    /// example because it expired), so versions written in between may have
+    /// been missed.
+    pub gap: bool,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub diff: Vec<JsonChange>,
+}
+
+#[derive(Default)]
+struct KeyState {
+    /// The current version has been recorded.
+    ready: bool,
+    /// The key existed when watching started, so its history is not sent:
+    /// until its current version is recorded, it is baselined rather than
+    /// polled.
+    existing: bool,
+    version: Option<String>,
+    created_time: Option<String>,
+    value: Option<Value>,
+}
+
+/// Values that are not valid JSON are reported as JSON strings.
+fn parse_value(data: String) -> Value {
+    serde_json::from_str(&data).unwrap_or(Value::String(data))
+}
+
+struct Watcher {
+    datastore: RbxDataStore,
+    params: DataStoreWatch,
+    throttle: Throttle,
+    states: HashMap<(String, String), KeyState>,
+}
+
+impl Watcher {
+    async fn keys(&mut self) -> Result<Vec<ListEntriesKey>, Error> {
+        let scope = self
+            .params
+            .scope
+            .clone()
+            .unwrap_or_else(|| "global".to_string());
+        let prefix = match &self.params.keys {
+            WatchKeys::Keys(keys) => {
+                return Ok(keys
+                    .iter()
+                    .map(|key| ListEntriesKey {
+                        scope: scope.clone(),
+                        key: key.clone(),
+                    })
+                    .collect())
+            }
+            WatchKeys::Prefix(prefix) => prefix.clone(),
+        };
+        let mut pager = self.datastore.key_pager(DataStoreListEntries {
+            name: self.params.name.clone(),
+            scope: Some(scope),
+            all_scopes: false,
+            prefix: Some(prefix),
+            limit: ReturnLimit(100),
+            cursor: None,
+        });
+        let mut keys = vec![];
+        loop {
+            self.throttle.wait().await;
+            match pager.next_page().await? {
+                Some(page) => keys.extend(page),
+                None => break,
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn list_versions(
+        &mut self,
+        key: &ListEntriesKey,
+        start_time: Option<String>,
+        sort_order: &str,
+    ) -> Result<Vec<ListEntryVersion>, Error> {
+        self.throttle.wait().await;
+        let res = self
+            .datastore
+            .list_entry_versions(&DataStoreListEntryVersions {
+                name: self.params.name.clone(),
+                scope: Some(key.scope.clone()),
+                key: key.key.clone(),
+                start_time,
+                end_time: None,
+                sort_order: sort_order.to_string(),
+                limit: ReturnLimit(100),
+                cursor: None,
+            })
+            .await?;
+        Ok(res.versions)
+    }
+
+    async fn get_value(&mut self, key: &ListEntriesKey, version: &str) -> Result<Value, Error> {
+        self.throttle.wait().await;
+        let data = self
+            .datastore
+            .get_entry_version(&DataStoreGetEntryVersion {
+                name: self.params.name.clone(),
+                scope: Some(key.scope.clone()),
+                key: key.key.clone(),
+                version_id: version.to_string(),
+            })
+            .await?;
+        Ok(parse_value(data))
+    }
+
+    /// Record the latest version of a key without sending events.
+    async fn baseline(&mut self, key: &ListEntriesKey, state: &mut KeyState) -> Result<(), Error> {
+        let versions = self.list_versions(key, None, "Descending").await?;
+        if let Some(latest) = versions.into_iter().next() {
+            state.value = if latest.deleted {
+                None
+            } else {
+                Some(self.get_value(key, &latest.version).await?)
+            };
+            state.version = Some(latest.version);
+            state.created_time = Some(latest.created_time);
+        }
+        state.ready = true;
+        Ok(())
+    }
+
+    /// Send an event for every version written since the last poll.
+    async fn poll(
+        &mut self,
+        key: &ListEntriesKey,
+        state: &mut KeyState,
+        tx: &mpsc::Sender<Result<DataStoreWatchEvent, Error>>,
+    ) -> Result<(), Error> {
+        state.ready = true;
+        let versions = self
+            .list_versions(key, state.created_time.clone(), "Ascending")
+            .await?;
+        // The start time is inclusive, so skip up to the last seen version.
+        // If it is not listed, every version is sent and the gap is flagged,
+        // rather than skipping them and never moving past it.
+        let (skip, mut gap) = match &state.version {
+            Some(seen) => match versions.iter().position(|v| &v.version == seen) {
+                Some(i) => (i + 1, false),
+                None => (0, true),
+            },
+            None => (0, false),
+        };
+        for version in &versions[skip..] {
+            let new_value = if version.deleted {
+                None
+            } else {
+                Some(self.get_value(key, &version.version).await?)
+            };
+            let changes = diff(
+                state.value.as_ref().unwrap_or(&Value::Null),
+                new_value.as_ref().unwrap_or(&Value::Null),
+            );
+            let event = DataStoreWatchEvent {
+                scope: key.scope.clone(),
+                key: key.key.clone(),
+                version: version.version.clone(),
+                previous_version: state.version.replace(version.version.clone()),
+                created_time: version.created_time.clone(),
+                deleted: version.deleted,
+                gap: std::mem::take(&mut gap),
+                old_value: std::mem::replace(&mut state.value, new_value.clone()),
+                new_value,
+                diff: changes,
+            };
+            state.created_time = Some(version.created_time.clone());
+            if tx.send(Ok(event)).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(mut self, tx: mpsc::Sender<Result<DataStoreWatchEvent, Error>>) {
+        let mut first = true;
+        while !tx.is_closed() {
+            let next_poll = Instant::now() + self.params.poll_interval;
+            let keys = match self.keys().await {
+                Ok(keys) => keys,
+                Err(err) => {
+                    // Keys are only baselined once a listing succeeds, so
+                    // the first poll is retried as a first poll.
+                    if tx.send(Err(err)).await.is_err() {
+                        return;
+                    }
+                    sleep_until(next_poll).await;
+                    continue;
+                }
+            };
+            let mut listed = HashSet::new();
+            for key in keys {
+                let id = (key.scope.clone(), key.key.clone());
+                listed.insert(id.clone());
+                let mut state = self.states.remove(&id).unwrap_or_default();
+                state.existing |= first;
+                // Keys found after the first poll are new, so all of their
+                // versions are sent. Existing keys whose baseline failed are
+                // baselined again instead.
+                let res = if !state.ready && state.existing {
+                    self.baseline(&key, &mut state).await
+                } else {
+                    self.poll(&key, &mut state, &tx).await
+                };
+                self.states.insert(id, state);
+                if let Err(err) = res {
+                    if tx.send(Err(err)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            // Keys that are no longer listed were deleted; poll them once
+            // more to send the deletion. On error they are tried again at
+            // the next poll. Keys that were never baselined are forgotten,
+            // since there is no known version to send changes from.
+            let unlisted: Vec<(String, String)> = self
+                .states
+                .keys()
+                .filter(|id| !listed.contains(*id))
+                .cloned()
+                .collect();
+            for id in unlisted {
+                let mut state = self.states.remove(&id).unwrap_or_default();
+                if !state.ready {
+                    continue;
+                }
+                let key = ListEntriesKey {
+                    scope: id.0.clone(),
+                    key: id.1.clone(),
+                };
+                if let Err(err) = self.poll(&key, &mut state, &tx).await {
+                    self.states.insert(id, state);
+                    if tx.send(Err(err)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            first = false;
+            sleep_until(next_poll).await;
+        }
+    }
+}
+
+/// Start watching keys. Events are sent until the receiver is dropped.
+///
+/// Errors while polling are sent through the channel, and the key is polled
+/// again at the next interval.
+pub fn watch(
+    datastore: &RbxDataStore,
+    params: DataStoreWatch,
+) -> mpsc::Receiver<Result<DataStoreWatchEvent, Error>> {
+    let (tx, rx) = mpsc::channel(64);
+    let watcher = Watcher {
+        datastore: datastore.clone(),
+        throttle: Throttle::new(params.max_requests_per_minute),
+        params,
+        states: HashMap::new(),
+    };
+    tokio::spawn(watcher.run(tx));
+    rx
+}
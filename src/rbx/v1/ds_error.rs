@@ -17,7 +17,7 @@ pub struct DataStoreErrorDetail {
     pub datastore_error_code: DataStoreErrorCode,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataStoreErrorCode {
     ContentLengthRequired,
     InvalidUniverseId,
@@ -47,6 +47,15 @@ pub enum DataStoreErrorCode {
     Unknown,
 }
 
+impl DataStoreErrorResponse {
+    /// Returns `true` if any of the error details carry the given code.
+    pub fn has_code(&self, code: DataStoreErrorCode) -> bool {
+        self.error_details
+            .iter()
+            .any(|detail| detail.datastore_error_code == code)
+    }
+}
+
 impl fmt::Display for DataStoreErrorResponse {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let details = self
@@ -4,6 +4,12 @@ use serde_json::json;
 use crate::rbx::error::Error;
 use crate::rbx::v1::UniverseId;
 
+/// Maximum size of a message, in bytes.
+pub const MAX_MESSAGE_BYTES: usize = 1024;
+
+/// Maximum length of a topic, in characters.
+pub const MAX_TOPIC_CHARS: usize = 80;
+
 /// Message publishing parameters.
 pub struct PublishMessageParams {
     pub api_key: String,
@@ -12,9 +18,39 @@ pub struct PublishMessageParams {
     pub message: String,
 }
 
+/// Check a topic and message against the MessagingService limits, so they
+/// are rejected before being sent.
+pub fn validate_message(topic: &str, message: &str) -> Result<(), Error> {
+    if topic.is_empty() {
+        return Err(Error::MessagingError("topic is empty".to_string()));
+    }
+    let topic_chars = topic.chars().count();
+    if topic_chars > MAX_TOPIC_CHARS {
+        return Err(Error::MessagingError(format!(
+            "topic is {topic_chars} characters long, more than the maximum of {MAX_TOPIC_CHARS}"
+        )));
+    }
+    if message.len() > MAX_MESSAGE_BYTES {
+        return Err(Error::MessagingError(format!(
+            "message is {} bytes long, more than the maximum of {MAX_MESSAGE_BYTES}",
+            message.len()
+        )));
+    }
+    Ok(())
+}
+
 /// Publish a message.
 pub async fn publish_message(params: &PublishMessageParams) -> Result<(), Error> {
-    let client = reqwest::Client::new();
+    publish_message_with_client(&reqwest::Client::new(), params).await
+}
+
+/// Publish a message through an existing HTTP client, so that connections
+/// are reused across messages.
+pub async fn publish_message_with_client(
+    client: &reqwest::Client,
+    params: &PublishMessageParams,
+) -> Result<(), Error> {
+    validate_message(&params.topic, &params.message)?;
     let url = format!(
         "https://apis.roblox.com/messaging-service/v1/universes/{universeId}/topics/{topic}",
         universeId = params.universe_id,
@@ -0,0 +1,116 @@
+//! Publishing many messages under the MessagingService rate limit.
+//!
+//! Messages are published one at a time, in order, and spaced so that no
+//! more than `max_requests_per_minute` are sent. The limit of a universe is
+//! 150 requests per minute plus 60 per player in the experience. Messages
+//! that are invalid or rejected are recorded in the report, and publishing
+//! continues with the next one.
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::Throttle};
+
+use super::{messaging::validate_message, RbxMessaging};
+
+/// Default rate limit, the limit of a universe without players.
+pub const DEFAULT_MAX_REQUESTS_PER_MINUTE: u32 = 150;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageBatchFailure {
+    /// Position of the message in the batch, starting at 0.
+    pub index: u64,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageBatchReport {
+    pub published: u64,
+    pub failed: u64,
+    pub failures: Vec<MessageBatchFailure>,
+}
+
+/// Publishes messages to a topic under a rate limit. Obtained through
+/// `RbxMessaging::batch_publisher`.
+pub struct MessageBatchPublisher {
+    messaging: RbxMessaging,
+    throttle: Throttle,
+    count: u64,
+    report: MessageBatchReport,
+}
+
+impl MessageBatchPublisher {
+    pub fn new(messaging: RbxMessaging, max_requests_per_minute: u32) -> Self {
+        Self {
+            messaging,
+            throttle: Throttle::new(max_requests_per_minute),
+            count: 0,
+            report: MessageBatchReport::default(),
+        }
+    }
+
+    /// Publish a message, waiting for the rate limit first. The result is
+    /// also recorded in the report.
+    pub async fn publish(&mut self, message: &str) -> Result<(), Error> {
+        let res = self.send(message).await;
+        self.record(res)
+    }
+
+    /// Serialize a value as JSON and publish it.
+    pub async fn publish_json<T: Serialize>(&mut self, message: &T) -> Result<(), Error> {
+        let res = match serde_json::to_string(message) {
+            Ok(message) => self.send(&message).await,
+            Err(err) => Err(err.into()),
+        };
+        self.record(res)
+    }
+
+    async fn send(&mut self, message: &str) -> Result<(), Error> {
+        // Invalid messages are not sent, so they do not count against the
+        // rate limit.
+        validate_message(&self.messaging.topic, message)?;
+        self.throttle.wait().await;
+        self.messaging.publish(message).await
+    }
+
+    fn record(&mut self, res: Result<(), Error>) -> Result<(), Error> {
+        let index = self.count;
+        self.count += 1;
+        match &res {
+            Ok(()) => self.report.published += 1,
+            Err(err) => {
+                self.report.failed += 1;
+                self.report.failures.push(MessageBatchFailure {
+                    index,
+                    error: err.to_string(),
+                });
+            }
+        }
+        res
+    }
+
+    pub fn report(&self) -> &MessageBatchReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> MessageBatchReport {
+        self.report
+    }
+}
+
+/// Publish every message, in order, under the rate limit.
+pub async fn publish_batch<I, S>(
+    messaging: &RbxMessaging,
+    messages: I,
+    max_requests_per_minute: u32,
+) -> MessageBatchReport
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut publisher = MessageBatchPublisher::new(messaging.clone(), max_requests_per_minute);
+    for message in messages {
+        let _ = publisher.publish(message.as_ref()).await;
+    }
+    publisher.into_report()
+}
@@ -0,0 +1,171 @@
+//! Publishing a message to many universes at once.
+//!
+//! The same topic and message are published concurrently to every universe,
+//! and the outcome of each one is recorded in the report. Failures can be
+//! retried: rejected requests (400, 401 and 403, such as "publish not
+//! allowed on place") are not, since sending them again would not change
+//! the outcome.
+//!
+//! Universes can be listed directly, or by name through a group defined in a
+//! JSON config file:
+//!
+//! ```json
+//! {
+//!   "universeGroups": {
+//!     "prod": [1234567890, 2345678901],
+//!     "test": [3456789012]
+//!   }
+//! }
+//! ```
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, types::UniverseId, util::map_concurrent};
+
+use super::{messaging::validate_message, RbxMessaging};
+
+/// Named groups of universes, read from a JSON config file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastConfig {
+    #[serde(default)]
+    pub universe_groups: HashMap<String, Vec<UniverseId>>,
+}
+
+impl BroadcastConfig {
+    /// Read a config from a JSON file.
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| Error::FileLoadError(format!("{path}: {err}")))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Universes of a named group.
+    pub fn group(&self, name: &str) -> Result<&[UniverseId], Error> {
+        self.universe_groups
+            .get(name)
+            .map(|ids| ids.as_slice())
+            .ok_or_else(|| Error::MessagingError(format!("unknown universe group {name}")))
+    }
+}
+
+pub struct BroadcastMessage {
+    pub universe_ids: Vec<UniverseId>,
+    /// Maximum number of universes published to at once.
+    pub concurrency: usize,
+    /// Number of times a failed publish is retried.
+    pub retries: u32,
+    /// Wait before each retry.
+    pub retry_delay: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastResult {
+    pub universe_id: UniverseId,
+    pub published: bool,
+    pub attempts: u32,
+    /// HTTP status of the last failed attempt, if the server responded.
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastReport {
+    pub published: u64,
+    pub failed: u64,
+    /// One result per universe, in the order they were given.
+    pub results: Vec<BroadcastResult>,
+}
+
+fn is_retryable(err: &Error) -> bool {
+    match err {
+        Error::HttpStatusError { code, .. } => !matches!(code, 400 | 401 | 403),
+        Error::MessagingError(_) => false,
+        _ => true,
+    }
+}
+
+async fn publish_to_universe(
+    messaging: RbxMessaging,
+    message: Arc<String>,
+    retries: u32,
+    retry_delay: Duration,
+) -> BroadcastResult {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let err = match messaging.publish(&message).await {
+            Ok(()) => {
+                return BroadcastResult {
+                    universe_id: messaging.universe_id,
+                    published: true,
+                    attempts,
+                    status: None,
+                    error: None,
+                }
+            }
+            Err(err) => err,
+        };
+        if attempts > retries || !is_retryable(&err) {
+            let status = match &err {
+                Error::HttpStatusError { code, .. } => Some(*code),
+                _ => None,
+            };
+            return BroadcastResult {
+                universe_id: messaging.universe_id,
+                published: false,
+                attempts,
+                status,
+                error: Some(err.to_string()),
+            };
+        }
+        tokio::time::sleep(retry_delay).await;
+    }
+}
+
+/// Publish a message to the topic of `messaging` in every universe of the
+/// broadcast. Universes listed more than once are published to once.
+///
+/// An invalid topic or message is rejected before anything is sent;
+/// otherwise every universe has a result in the report.
+pub async fn broadcast(
+    messaging: &RbxMessaging,
+    message: &str,
+    broadcast: &BroadcastMessage,
+) -> Result<BroadcastReport, Error> {
+    validate_message(&messaging.topic, message)?;
+    let mut universe_ids: Vec<UniverseId> = Vec::with_capacity(broadcast.universe_ids.len());
+    for universe_id in &broadcast.universe_ids {
+        if !universe_ids.iter().any(|id| id.0 == universe_id.0) {
+            universe_ids.push(*universe_id);
+        }
+    }
+    if universe_ids.is_empty() {
+        return Err(Error::MessagingError(
+            "no universes to publish to".to_string(),
+        ));
+    }
+    let message = Arc::new(message.to_string());
+    let (retries, retry_delay) = (broadcast.retries, broadcast.retry_delay);
+    let results = map_concurrent(universe_ids, broadcast.concurrency, |universe_id| {
+        let messaging = RbxMessaging {
+            universe_id,
+            ..messaging.clone()
+        };
+        publish_to_universe(messaging, message.clone(), retries, retry_delay)
+    })
+    .await;
+    let mut report = BroadcastReport::default();
+    for result in results {
+        if result.published {
+            report.published += 1;
+        } else {
+            report.failed += 1;
+        }
+        report.results.push(result);
+    }
+    Ok(report)
+}
@@ -3,15 +3,34 @@
 //! Most usage should go through the `RbxCloud` struct.
 pub mod assets;
 pub mod datastore;
-pub(crate) mod ds_error;
+pub mod datastore_bulk_delete;
+pub mod datastore_cache;
+pub mod datastore_copy;
+pub mod datastore_diff;
+pub mod datastore_dump;
+pub mod datastore_erasure;
+pub mod datastore_query;
+pub mod datastore_restore;
+pub mod datastore_schema;
+pub mod datastore_snapshot;
+pub mod datastore_stats;
+pub mod datastore_watch;
+pub mod ds_error;
 pub mod experience;
 pub mod messaging;
+pub mod messaging_batch;
+pub mod messaging_broadcast;
 pub mod ordered_datastore;
+pub mod ordered_datastore_import;
+pub mod ordered_datastore_leaderboard;
+pub mod ordered_datastore_rollover;
+pub mod profile_store;
 
-use crate::rbx::error;
+use crate::rbx::{error, json_schema::JsonSchema};
 use assets::{ArchiveAssetParams, AssetInfo, GetAssetOperationParams, GetAssetParams};
 pub use experience::PublishVersionType;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{io::Write, sync::Arc};
 
 use self::{
     assets::{
@@ -19,20 +38,44 @@ use self::{
         CreateAssetParamsWithContents, UpdateAssetParams,
     },
     datastore::{
-        DeleteEntryParams, GetEntryParams, GetEntryVersionParams, IncrementEntryParams,
-        ListDataStoresParams, ListDataStoresResponse, ListEntriesParams, ListEntriesResponse,
+        DeleteEntryParams, EntryAttributes, GetEntryParams, GetEntryVersionParams,
+        GetEntryWithMetadataResponse, IncrementEntryParams, ListDataStoresParams,
+        ListDataStoresResponse, ListEntriesKey, ListEntriesParams, ListEntriesResponse,
         ListEntryVersionsParams, ListEntryVersionsResponse, SetEntryParams, SetEntryResponse,
     },
+    datastore_bulk_delete::{DataStoreBulkDelete, DataStoreBulkDeleteReport},
+    datastore_cache::DataStoreCache,
+    datastore_copy::{DataStoreCopyEntries, DataStoreCopyReport},
+    datastore_dump::{
+        DataStoreExportEntries, DataStoreExportReport, DataStoreImportEntries,
+        DataStoreImportReport, DumpedEntry, LoadedEntries,
+    },
+    datastore_erasure::{DataStoreErasure, DataStoreErasureReport},
+    datastore_query::{DataStoreQuery, QueryMatch},
+    datastore_restore::{DataStoreRestoreEntries, DataStoreRestoreReport},
+    datastore_schema::{DataStoreSchema, DataStoreValidationReport},
+    datastore_snapshot::SnapshotRecord,
+    datastore_stats::{DataStoreStatsParams, DataStoreStatsReport},
+    datastore_watch::{DataStoreWatch, DataStoreWatchEvent},
     error::Error,
     experience::{PublishExperienceParams, PublishExperienceResponse},
     messaging::PublishMessageParams,
-    ordered_datastore::{
-        OrderedCreateEntryParams, OrderedEntry, OrderedEntryParams, OrderedIncrementEntryParams,
-        OrderedListEntriesParams, OrderedListEntriesResponse, OrderedUpdateEntryParams,
+    messaging_batch::{MessageBatchPublisher, MessageBatchReport},
+    messaging_broadcast::{BroadcastMessage, BroadcastReport},
+    ordered_datastore::{OrderBy, OrderedEntry, OrderedFilter, OrderedListEntriesResponse},
+    ordered_datastore_import::{
+        OrderedDataStoreImportEntries, OrderedDataStoreImportReport, OrderedImportFailure,
+        OrderedImportRow,
     },
+    ordered_datastore_leaderboard::{LeaderboardFormat, OrderedDataStoreLeaderboard, RankedEntry},
+    ordered_datastore_rollover::{OrderedDataStoreRollover, RolloverState},
+    profile_store::{ProfileEnvelope, StoredProfile},
 };
 
-use super::types::{PageSize, PlaceId, ReturnLimit, RobloxUserId, UniverseId};
+use super::{
+    types::{PageSize, PlaceId, ReturnLimit, RobloxUserId, UniverseId},
+    v2::{self, OrderedDataStoreClient},
+};
 
 impl std::fmt::Display for UniverseId {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -96,28 +139,91 @@ impl RbxExperience {
     }
 }
 
+#[derive(Clone)]
 pub struct RbxMessaging {
     pub api_key: String,
     pub universe_id: UniverseId,
     pub topic: String,
+    /// HTTP client reused across publishes.
+    client: reqwest::Client,
 }
 
 impl RbxMessaging {
-    /// Publish a message.
+    pub fn new(api_key: &str, universe_id: UniverseId, topic: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            universe_id,
+            topic: topic.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Publish a message. The topic and message are checked against the
+    /// MessagingService limits before being sent.
     pub async fn publish(&self, message: &str) -> Result<(), Error> {
-        messaging::publish_message(&PublishMessageParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            topic: self.topic.clone(),
-            message: message.to_string(),
-        })
+        messaging::publish_message_with_client(
+            &self.client,
+            &PublishMessageParams {
+                api_key: self.api_key.clone(),
+                universe_id: self.universe_id,
+                topic: self.topic.clone(),
+                message: message.to_string(),
+            },
+        )
         .await
     }
+
+    /// Serialize a value as JSON and publish it. Subscribers receive the
+    /// JSON string, which can be decoded with `HttpService:JSONDecode`.
+    pub async fn publish_json<T: Serialize>(&self, message: &T) -> Result<(), Error> {
+        self.publish(&serde_json::to_string(message)?).await
+    }
+
+    /// Create a publisher that spaces messages to stay under a rate limit
+    /// (see `messaging_batch::DEFAULT_MAX_REQUESTS_PER_MINUTE`).
+    pub fn batch_publisher(&self, max_requests_per_minute: u32) -> MessageBatchPublisher {
+        MessageBatchPublisher::new(self.clone(), max_requests_per_minute)
+    }
+
+    /// Publish every message, in order, under a rate limit. Failed messages
+    /// are recorded in the report.
+    pub async fn publish_batch<I, S>(
+        &self,
+        messages: I,
+        max_requests_per_minute: u32,
+    ) -> MessageBatchReport
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        messaging_batch::publish_batch(self, messages, max_requests_per_minute).await
+    }
+
+    /// Publish a message to this topic in every universe of the broadcast,
+    /// concurrently, instead of only in this universe. The outcome of each
+    /// universe is recorded in the report.
+    pub async fn broadcast(
+        &self,
+        message: &str,
+        broadcast: &BroadcastMessage,
+    ) -> Result<BroadcastReport, Error> {
+        messaging_broadcast::broadcast(self, message, broadcast).await
+    }
 }
 
+#[derive(Clone)]
 pub struct RbxDataStore {
     pub api_key: String,
     pub universe_id: UniverseId,
+    /// Schemas that values are validated against before being written.
+    pub(crate) schemas: Vec<DataStoreSchema>,
+    /// Check writes against the documented DataStore limits before sending
+    /// them (see `datastore::preflight_key`). Enabled by default.
+    pub(crate) preflight: bool,
+    /// Read-through cache for entry reads (see `datastore_cache`).
+    pub(crate) cache: Option<Arc<dyn DataStoreCache>>,
+    /// Check that a cached entry is still the latest version before using it.
+    pub(crate) validate_cached_versions: bool,
 }
 
 pub struct DataStoreListStores {
@@ -135,6 +241,28 @@ pub struct DataStoreListEntries {
     pub cursor: Option<String>,
 }
 
+/// Pages through the keys of a DataStore, following the list cursor.
+///
+/// Obtained through `RbxDataStore::key_pager()`.
+pub struct DataStoreKeyPager {
+    datastore: RbxDataStore,
+    params: DataStoreListEntries,
+    finished: bool,
+}
+
+impl DataStoreKeyPager {
+    /// Get the next page of keys, or `None` once every page has been read.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<ListEntriesKey>>, Error> {
+        if self.finished {
+            return Ok(None);
+        }
+        let res = self.datastore.list_entries(&self.params).await?;
+        self.params.cursor = res.next_page_cursor.filter(|cursor| !cursor.is_empty());
+        self.finished = self.params.cursor.is_none();
+        Ok(Some(res.keys))
+    }
+}
+
 pub struct DataStoreGetEntry {
     pub name: String,
     pub scope: Option<String>,
@@ -148,7 +276,7 @@ pub struct DataStoreSetEntry {
     pub match_version: Option<String>,
     pub exclusive_create: Option<bool>,
     pub roblox_entry_user_ids: Option<Vec<RobloxUserId>>,
-    pub roblox_entry_attributes: Option<String>,
+    pub roblox_entry_attributes: Option<EntryAttributes>,
     pub data: String,
 }
 
@@ -157,7 +285,7 @@ pub struct DataStoreIncrementEntry {
     pub scope: Option<String>,
     pub key: String,
     pub roblox_entry_user_ids: Option<Vec<RobloxUserId>>,
-    pub roblox_entry_attributes: Option<String>,
+    pub roblox_entry_attributes: Option<EntryAttributes>,
     pub increment_by: f64,
 }
 
@@ -201,6 +329,25 @@ impl RbxDataStore {
         .await
     }
 
+    /// List the names of every DataStore within the experience, following
+    /// all pages.
+    pub async fn list_all_store_names(&self, prefix: Option<String>) -> Result<Vec<String>, Error> {
+        let mut names = vec![];
+        let mut params = DataStoreListStores {
+            prefix,
+            limit: ReturnLimit(100),
+            cursor: None,
+        };
+        loop {
+            let res = self.list_stores(&params).await?;
+            names.extend(res.datastores.into_iter().map(|ds| ds.name));
+            params.cursor = res.next_page_cursor.filter(|cursor| !cursor.is_empty());
+            if params.cursor.is_none() {
+                return Ok(names);
+            }
+        }
+    }
+
     /// List key entries in a specific DataStore.
     pub async fn list_entries(
         &self,
@@ -221,6 +368,10 @@ impl RbxDataStore {
 
     /// Get the entry string representation of a specific key.
     pub async fn get_entry_string(&self, params: &DataStoreGetEntry) -> Result<String, Error> {
+        if let Some(cache) = &self.cache {
+            let entry = datastore_cache::get_cached(self, cache.as_ref(), params).await?;
+            return Ok(entry.data);
+        }
         datastore::get_entry_string(&GetEntryParams {
             api_key: self.api_key.clone(),
             universe_id: self.universe_id,
@@ -231,11 +382,43 @@ impl RbxDataStore {
         .await
     }
 
+    /// Get the entry string representation of a specific key, along with
+    /// its version, user IDs, attributes, and checksum.
+    pub async fn get_entry_with_metadata(
+        &self,
+        params: &DataStoreGetEntry,
+    ) -> Result<GetEntryWithMetadataResponse, Error> {
+        if let Some(cache) = &self.cache {
+            return datastore_cache::get_cached(self, cache.as_ref(), params).await;
+        }
+        self.get_entry_with_metadata_uncached(params).await
+    }
+
+    /// Same as `get_entry_with_metadata()`, but always reads from Roblox,
+    /// bypassing the cache.
+    pub async fn get_entry_with_metadata_uncached(
+        &self,
+        params: &DataStoreGetEntry,
+    ) -> Result<GetEntryWithMetadataResponse, Error> {
+        datastore::get_entry_with_metadata(&GetEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            datastore_name: params.name.clone(),
+            scope: params.scope.clone(),
+            key: params.key.clone(),
+        })
+        .await
+    }
+
     /// Get the entry of a specific key, deserialized as `T`.
     pub async fn get_entry<T: DeserializeOwned>(
         &self,
         params: &DataStoreGetEntry,
     ) -> Result<T, Error> {
+        if let Some(cache) = &self.cache {
+            let entry = datastore_cache::get_cached(self, cache.as_ref(), params).await?;
+            return Ok(serde_json::from_str::<T>(&entry.data)?);
+        }
         datastore::get_entry::<T>(&GetEntryParams {
             api_key: self.api_key.clone(),
             universe_id: self.universe_id,
@@ -247,8 +430,19 @@ impl RbxDataStore {
     }
 
     /// Set (or create) the entry value of a specific key.
+    ///
+    /// The value is validated against the registered schemas first.
     pub async fn set_entry(&self, params: &DataStoreSetEntry) -> Result<SetEntryResponse, Error> {
-        datastore::set_entry(&SetEntryParams {
+        if self.preflight {
+            datastore::preflight_key(&params.name, params.scope.as_deref(), &params.key)?;
+            datastore::preflight_metadata(
+                params.roblox_entry_user_ids.as_deref(),
+                params.roblox_entry_attributes.as_ref(),
+            )?;
+            datastore::preflight_value(&params.data)?;
+        }
+        self.validate_entry(&params.name, &params.key, &params.data)?;
+        let write_params = SetEntryParams {
             api_key: self.api_key.clone(),
             universe_id: self.universe_id,
             datastore_name: params.name.clone(),
@@ -259,7 +453,14 @@ impl RbxDataStore {
             roblox_entry_user_ids: params.roblox_entry_user_ids.clone(),
             roblox_entry_attributes: params.roblox_entry_attributes.clone(),
             data: params.data.clone(),
-        })
+        };
+        datastore_cache::invalidating_write(
+            self,
+            &params.name,
+            params.scope.as_deref(),
+            &params.key,
+            datastore::set_entry(&write_params),
+        )
         .await
     }
 
@@ -271,7 +472,14 @@ impl RbxDataStore {
     /// If the value _does_ exist, but it is _not_ a number, then the increment
     /// process will fail, and a DataStore error will be returned in the result.
     pub async fn increment_entry(&self, params: &DataStoreIncrementEntry) -> Result<f64, Error> {
-        datastore::increment_entry(&IncrementEntryParams {
+        if self.preflight {
+            datastore::preflight_key(&params.name, params.scope.as_deref(), &params.key)?;
+            datastore::preflight_metadata(
+                params.roblox_entry_user_ids.as_deref(),
+                params.roblox_entry_attributes.as_ref(),
+            )?;
+        }
+        let write_params = IncrementEntryParams {
             api_key: self.api_key.clone(),
             universe_id: self.universe_id,
             datastore_name: params.name.clone(),
@@ -280,19 +488,36 @@ impl RbxDataStore {
             roblox_entry_user_ids: params.roblox_entry_user_ids.clone(),
             roblox_entry_attributes: params.roblox_entry_attributes.clone(),
             increment_by: params.increment_by,
-        })
+        };
+        datastore_cache::invalidating_write(
+            self,
+            &params.name,
+            params.scope.as_deref(),
+            &params.key,
+            datastore::increment_entry(&write_params),
+        )
         .await
     }
 
     /// Delete an entry.
     pub async fn delete_entry(&self, params: &DataStoreDeleteEntry) -> Result<(), Error> {
-        datastore::delete_entry(&DeleteEntryParams {
+        if self.preflight {
+            datastore::preflight_key(&params.name, params.scope.as_deref(), &params.key)?;
+        }
+        let write_params = DeleteEntryParams {
             api_key: self.api_key.clone(),
             universe_id: self.universe_id,
             datastore_name: params.name.clone(),
             scope: params.scope.clone(),
             key: params.key.clone(),
-        })
+        };
+        datastore_cache::invalidating_write(
+            self,
+            &params.name,
+            params.scope.as_deref(),
+            &params.key,
+            datastore::delete_entry(&write_params),
+        )
         .await
     }
 
@@ -333,8 +558,276 @@ impl RbxDataStore {
         })
         .await
     }
+
+    /// Get the entry value of a specific version, along with the user IDs and
+    /// attributes the entry had at that version.
+    pub async fn get_entry_version_with_metadata(
+        &self,
+        params: &DataStoreGetEntryVersion,
+    ) -> Result<GetEntryWithMetadataResponse, Error> {
+        datastore::get_entry_version_with_metadata(&GetEntryVersionParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            datastore_name: params.name.clone(),
+            scope: params.scope.clone(),
+            key: params.key.clone(),
+            version_id: params.version_id.clone(),
+        })
+        .await
+    }
+
+    /// Register a JSON Schema for the keys matching `key_pattern` in the
+    /// DataStores matching `name_pattern`. Patterns support `*` and `?`
+    /// wildcards.
+    ///
+    /// Values written through `set_entry()` must match every schema
+    /// registered for their key, otherwise `Error::SchemaValidationError`
+    /// is returned and nothing is written.
+    pub fn register_schema(&mut self, name_pattern: &str, key_pattern: &str, schema: JsonSchema) {
+        self.schemas.push(DataStoreSchema {
+            name: name_pattern.to_string(),
+            key: key_pattern.to_string(),
+            schema,
+        });
+    }
+
+    /// Same as `register_schema()`, for chaining.
+    pub fn with_schema(
+        mut self,
+        name_pattern: &str,
+        key_pattern: &str,
+        schema: JsonSchema,
+    ) -> Self {
+        self.register_schema(name_pattern, key_pattern, schema);
+        self
+    }
+
+    /// Enable or disable the checks of writes against the documented
+    /// DataStore limits (see `datastore::preflight_key`). Enabled by default.
+    pub fn with_preflight(mut self, preflight: bool) -> Self {
+        self.preflight = preflight;
+        self
+    }
+
+    /// Same as `set_cache()` with a cache, for chaining.
+    pub fn with_cache(mut self, cache: Arc<dyn DataStoreCache>, validate_versions: bool) -> Self {
+        self.set_cache(Some(cache), validate_versions);
+        self
+    }
+
+    /// Serve entry reads from `cache`, or stop caching with `None`. When
+    /// `validate_versions` is set, a cached entry is only used if it is
+    /// still the latest version of its key, which costs a version listing
+    /// instead of a read.
+    pub fn set_cache(&mut self, cache: Option<Arc<dyn DataStoreCache>>, validate_versions: bool) {
+        self.cache = cache;
+        self.validate_cached_versions = validate_versions;
+    }
+
+    /// Validate a value against the schemas registered for its key.
+    pub fn validate_entry(&self, name: &str, key: &str, data: &str) -> Result<(), Error> {
+        datastore_schema::check_value(&self.schemas, name, key, data)
+    }
+
+    /// Validate existing entries against the registered schemas.
+    pub async fn validate_entries(
+        &self,
+        params: &DataStoreExportEntries,
+    ) -> Result<DataStoreValidationReport, Error> {
+        datastore_schema::validate_entries(self, params).await
+    }
+
+    /// Page through all keys matching the given list parameters.
+    ///
+    /// The `cursor` of the parameters is used as the starting point.
+    pub fn key_pager(&self, params: DataStoreListEntries) -> DataStoreKeyPager {
+        DataStoreKeyPager {
+            datastore: self.clone(),
+            params,
+            finished: false,
+        }
+    }
+
+    /// Copy entries from this DataStore into `destination`, which may live in
+    /// a different universe.
+    ///
+    /// Entry user IDs and attributes are carried over. Set `delete_source`
+    /// to move the entries instead.
+    pub async fn copy_entries(
+        &self,
+        destination: &RbxDataStore,
+        params: &DataStoreCopyEntries,
+    ) -> Result<DataStoreCopyReport, Error> {
+        datastore_copy::copy_entries(self, destination, params).await
+    }
+
+    /// Export matching entries as NDJSON (one `DumpedEntry` per line) into
+    /// `writer`. Entries that cannot be read are listed in the report.
+    pub async fn export_entries<W: Write>(
+        &self,
+        params: &DataStoreExportEntries,
+        writer: &mut W,
+    ) -> Result<DataStoreExportReport, Error> {
+        datastore_dump::export_entries(self, params, writer).await
+    }
+
+    /// Fetch the values and metadata of all matching entries into memory,
+    /// along with the entries that could not be read.
+    pub async fn load_entries(
+        &self,
+        params: &DataStoreExportEntries,
+    ) -> Result<LoadedEntries, Error> {
+        datastore_dump::load_entries(self, params).await
+    }
+
+    /// Write dumped entries (e.g. an export or a bulk-delete backup) into a
+    /// DataStore.
+    pub async fn import_entries(
+        &self,
+        params: &DataStoreImportEntries,
+        entries: Vec<DumpedEntry>,
+    ) -> Result<DataStoreImportReport, Error> {
+        datastore_dump::import_entries(self, params, entries).await
+    }
+
+    /// Collect the keys that `bulk_delete()` would remove, so they can be
+    /// reviewed first.
+    pub async fn collect_bulk_delete_keys(
+        &self,
+        params: &DataStoreBulkDelete,
+    ) -> Result<Vec<ListEntriesKey>, Error> {
+        datastore_bulk_delete::collect_keys(self, params).await
+    }
+
+    /// Delete many keys, writing each value to `backup` as NDJSON before it is
+    /// deleted. The backup can be restored with `import_entries()`.
+    pub async fn bulk_delete<W: Write>(
+        &self,
+        name: &str,
+        keys: Vec<ListEntriesKey>,
+        backup: &mut W,
+        concurrency: usize,
+    ) -> Result<DataStoreBulkDeleteReport, Error> {
+        datastore_bulk_delete::bulk_delete(self, name, keys, backup, concurrency).await
+    }
+
+    /// Preview a point-in-time restore. For each matching key, finds the latest
+    /// version at or before `params.at` and compares it with the current value.
+    ///
+    /// Nothing is written; pass the result to `apply_restore()` to perform it.
+    pub async fn plan_restore(
+        &self,
+        params: &DataStoreRestoreEntries,
+    ) -> Result<DataStoreRestoreReport, Error> {
+        datastore_restore::plan_restore(self, params).await
+    }
+
+    /// Write the versions found by `plan_restore()` back as the current values.
+    pub async fn apply_restore(
+        &self,
+        plan: &DataStoreRestoreReport,
+        concurrency: usize,
+    ) -> Result<DataStoreRestoreReport, Error> {
+        datastore_restore::apply_restore(self, plan, concurrency).await
+    }
+
+    /// Take a snapshot of every DataStore in the experience. At most one
+    /// snapshot is taken per UTC day.
+    pub async fn take_snapshot(&self, label: Option<String>) -> Result<SnapshotRecord, Error> {
+        datastore_snapshot::take_snapshot(self, label).await
+    }
+
+    /// Find the latest snapshot in `records` taken before the given entry
+    /// version was written. Its time can be used as the `at` of a restore.
+    pub async fn snapshot_before_version<'a>(
+        &self,
+        records: &'a [SnapshotRecord],
+        params: &DataStoreGetEntryVersion,
+    ) -> Result<&'a SnapshotRecord, Error> {
+        datastore_snapshot::snapshot_before_version(self, records, params).await
+    }
+
+    /// Watch keys for new versions. Every new version is sent through the
+    /// returned channel, with the old and new value and their JSON diff.
+    ///
+    /// Polling runs in a background task until the receiver is dropped.
+    pub fn watch(
+        &self,
+        params: DataStoreWatch,
+    ) -> tokio::sync::mpsc::Receiver<Result<DataStoreWatchEvent, Error>> {
+        datastore_watch::watch(self, params)
+    }
+
+    /// Search entries whose value matches a filter expression. Matches (or
+    /// their projections) are sent through the returned channel as they are
+    /// found.
+    pub fn query(
+        &self,
+        params: DataStoreQuery,
+    ) -> tokio::sync::mpsc::Receiver<Result<QueryMatch, Error>> {
+        datastore_query::query(self, params)
+    }
+
+    /// Report key counts per scope, value sizes, the largest keys and,
+    /// optionally, version counts for DataStores in the experience.
+    pub async fn stats(
+        &self,
+        params: &DataStoreStatsParams,
+    ) -> Result<DataStoreStatsReport, Error> {
+        datastore_stats::stats(self, params).await
+    }
+
+    /// Find every entry associated with a user, through key templates and/or
+    /// by scanning entry user ID metadata. Nothing is deleted; pass the
+    /// result to `apply_erasure()` to delete the entries.
+    pub async fn plan_erasure(
+        &self,
+        params: &DataStoreErasure,
+    ) -> Result<DataStoreErasureReport, Error> {
+        datastore_erasure::plan_erasure(self, params).await
+    }
+
+    /// Delete the entries found by `plan_erasure()`. The returned report
+    /// records what was removed and can be kept for auditing.
+    pub async fn apply_erasure(
+        &self,
+        plan: &DataStoreErasureReport,
+        concurrency: usize,
+    ) -> Result<DataStoreErasureReport, Error> {
+        datastore_erasure::apply_erasure(self, plan, concurrency).await
+    }
+
+    /// Read a ProfileService/ProfileStore profile.
+    pub async fn get_profile(&self, params: &DataStoreGetEntry) -> Result<StoredProfile, Error> {
+        profile_store::get_profile(self, params).await
+    }
+
+    /// Edit a ProfileService/ProfileStore profile. The edit is refused if a
+    /// game server holds the session lock, unless `force` is set or the
+    /// profile has not been saved for `profile_store::DEAD_SESSION_LOCK_AGE`.
+    pub async fn update_profile<F>(
+        &self,
+        params: &DataStoreGetEntry,
+        force: bool,
+        edit: F,
+    ) -> Result<StoredProfile, Error>
+    where
+        F: FnOnce(&mut ProfileEnvelope) -> Result<(), Error>,
+    {
+        profile_store::update_profile(self, params, force, edit).await
+    }
+
+    /// Push a global update to a ProfileService profile, returning its ID.
+    pub async fn push_global_update(
+        &self,
+        params: &DataStoreGetEntry,
+        data: serde_json::Value,
+    ) -> Result<u64, Error> {
+        profile_store::push_global_update(self, params, data).await
+    }
 }
 
+#[derive(Clone)]
 pub struct RbxOrderedDataStore {
     pub api_key: String,
     pub universe_id: UniverseId,
@@ -345,8 +838,29 @@ pub struct OrderedDataStoreListEntries {
     pub scope: Option<String>,
     pub max_page_size: Option<PageSize>,
     pub page_token: Option<String>,
-    pub order_by: Option<String>,
-    pub filter: Option<String>,
+    pub order_by: Option<OrderBy>,
+    pub filter: Option<OrderedFilter>,
+}
+
+/// Pages through the entries of an OrderedDataStore. Obtained through
+/// `RbxOrderedDataStore::entry_pager`.
+pub struct OrderedDataStoreEntryPager {
+    ordered_datastore: RbxOrderedDataStore,
+    params: OrderedDataStoreListEntries,
+    finished: bool,
+}
+
+impl OrderedDataStoreEntryPager {
+    /// Get the next page of entries, or `None` once every page has been read.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<OrderedEntry>>, Error> {
+        if self.finished {
+            return Ok(None);
+        }
+        let res = self.ordered_datastore.list_entries(&self.params).await?;
+        self.params.page_token = res.next_page_token.filter(|token| !token.is_empty());
+        self.finished = self.params.page_token.is_none();
+        Ok(Some(res.entries))
+    }
 }
 
 pub struct OrderedDataStoreCreateEntry {
@@ -378,22 +892,108 @@ pub struct OrderedDataStoreEntry {
 }
 
 impl RbxOrderedDataStore {
-    /// List key entries
+    /// The Cloud v2 client that requests are sent through.
+    fn v2(&self) -> OrderedDataStoreClient {
+        v2::Client::new(&self.api_key).ordered_datastore(self.universe_id)
+    }
+
+    /// List key entries. The filter is validated before the request is sent.
     pub async fn list_entries(
         &self,
         params: &OrderedDataStoreListEntries,
     ) -> Result<OrderedListEntriesResponse, Error> {
-        ordered_datastore::list_entries(&OrderedListEntriesParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            max_page_size: params.max_page_size,
-            page_token: params.page_token.clone(),
-            order_by: params.order_by.clone(),
-            filter: params.filter.clone(),
-        })
-        .await
+        let filter = match &params.filter {
+            Some(filter) => filter.as_query()?,
+            None => None,
+        };
+        let res = self
+            .v2()
+            .list_entries(
+                &params.name,
+                params.scope.clone(),
+                params
+                    .max_page_size
+                    .map(|size| u32::try_from(size.0).unwrap_or(u32::MAX)),
+                params
+                    .order_by
+                    .and_then(|order_by| order_by.as_v2_query())
+                    .map(str::to_string),
+                filter,
+                params.page_token.clone(),
+            )
+            .await?;
+        Ok(res.into())
+    }
+
+    /// Page through all entries matching the given list parameters.
+    ///
+    /// The `page_token` of the parameters is used as the starting point.
+    pub fn entry_pager(&self, params: OrderedDataStoreListEntries) -> OrderedDataStoreEntryPager {
+        OrderedDataStoreEntryPager {
+            ordered_datastore: self.clone(),
+            params,
+            finished: false,
+        }
+    }
+
+    /// Get the first `n` entries of a leaderboard, with their ranks.
+    pub async fn top_n(
+        &self,
+        leaderboard: &OrderedDataStoreLeaderboard,
+        n: usize,
+    ) -> Result<Vec<RankedEntry>, Error> {
+        ordered_datastore_leaderboard::top_n(self, leaderboard, n).await
+    }
+
+    /// Get the rank of an entry. Every entry ranked above it is listed, so
+    /// this costs one request per 100 entries above it.
+    pub async fn rank_of(
+        &self,
+        leaderboard: &OrderedDataStoreLeaderboard,
+        id: &str,
+    ) -> Result<RankedEntry, Error> {
+        ordered_datastore_leaderboard::rank_of(self, leaderboard, id).await
+    }
+
+    /// Get an entry with up to `k` entries ranked right above and below it.
+    pub async fn around(
+        &self,
+        leaderboard: &OrderedDataStoreLeaderboard,
+        id: &str,
+        k: usize,
+    ) -> Result<Vec<RankedEntry>, Error> {
+        ordered_datastore_leaderboard::around(self, leaderboard, id, k).await
+    }
+
+    /// Write a whole leaderboard (or its first `limit` entries) into
+    /// `writer`. Returns the number of exported entries.
+    pub async fn export_leaderboard<W: Write>(
+        &self,
+        leaderboard: &OrderedDataStoreLeaderboard,
+        limit: Option<usize>,
+        format: LeaderboardFormat,
+        writer: &mut W,
+    ) -> Result<u64, Error> {
+        ordered_datastore_leaderboard::export_leaderboard(self, leaderboard, limit, format, writer)
+            .await
+    }
+
+    /// Copy every entry of a leaderboard to an archive name or scope, verify
+    /// the archive, then delete or zero the original entries. The state
+    /// reported through the progress callback can be used to resume.
+    pub async fn rollover(&self, params: OrderedDataStoreRollover) -> Result<RolloverState, Error> {
+        ordered_datastore_rollover::rollover(self, params).await
+    }
+
+    /// Write rows (e.g. from `ordered_datastore_import::read_import_rows`)
+    /// with bounded concurrency and an optional rate limit. Invalid rows and
+    /// failed writes are recorded in the report.
+    pub async fn import_entries(
+        &self,
+        params: OrderedDataStoreImportEntries,
+        rows: Vec<Result<OrderedImportRow, OrderedImportFailure>>,
+    ) -> Result<OrderedDataStoreImportReport, Error> {
+        ordered_datastore_import::import_entries(self, params, rows).await
     }
 
     /// Create an entry
@@ -401,39 +1001,27 @@ impl RbxOrderedDataStore {
         &self,
         params: &OrderedDataStoreCreateEntry,
     ) -> Result<OrderedEntry, Error> {
-        ordered_datastore::create_entry(&OrderedCreateEntryParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            id: params.id.to_string(),
-            value: params.value,
-        })
-        .await
+        let entry = self
+            .v2()
+            .create_entry(&params.name, params.scope.clone(), &params.id, params.value)
+            .await?;
+        Ok(entry.into())
     }
 
     /// Get an entry
     pub async fn get_entry(&self, params: &OrderedDataStoreEntry) -> Result<OrderedEntry, Error> {
-        ordered_datastore::get_entry(&OrderedEntryParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            id: params.id.to_string(),
-        })
-        .await
+        let entry = self
+            .v2()
+            .get_entry(&params.name, params.scope.clone(), &params.id)
+            .await?;
+        Ok(entry.into())
     }
 
     /// Delete an entry
     pub async fn delete_entry(&self, params: &OrderedDataStoreEntry) -> Result<(), Error> {
-        ordered_datastore::delete_entry(&OrderedEntryParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            id: params.id.to_string(),
-        })
-        .await
+        self.v2()
+            .delete_entry(&params.name, params.scope.clone(), &params.id)
+            .await
     }
 
     /// Update an entry
@@ -441,16 +1029,17 @@ impl RbxOrderedDataStore {
         &self,
         params: &OrderedDataStoreUpdateEntry,
     ) -> Result<OrderedEntry, Error> {
-        ordered_datastore::update_entry(&OrderedUpdateEntryParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            id: params.id.to_string(),
-            value: params.value,
-            allow_missing: params.allow_missing,
-        })
-        .await
+        let entry = self
+            .v2()
+            .update_entry(
+                &params.name,
+                params.scope.clone(),
+                &params.id,
+                params.value,
+                params.allow_missing,
+            )
+            .await?;
+        Ok(entry.into())
     }
 
     /// Increment an entry
@@ -458,15 +1047,16 @@ impl RbxOrderedDataStore {
         &self,
         params: &OrderedDataStoreIncrementEntry,
     ) -> Result<OrderedEntry, Error> {
-        ordered_datastore::increment_entry(&OrderedIncrementEntryParams {
-            api_key: self.api_key.clone(),
-            universe_id: self.universe_id,
-            ordered_datastore_name: params.name.clone(),
-            scope: params.scope.clone(),
-            id: params.id.to_string(),
-            increment: params.increment,
-        })
-        .await
+        let entry = self
+            .v2()
+            .increment_entry(
+                &params.name,
+                params.scope.clone(),
+                &params.id,
+                params.increment,
+            )
+            .await?;
+        Ok(entry.into())
     }
 }
 
@@ -611,17 +1201,17 @@ impl RbxCloud {
     }
 
     pub fn messaging(&self, universe_id: UniverseId, topic: &str) -> RbxMessaging {
-        RbxMessaging {
-            api_key: self.api_key.clone(),
-            universe_id,
-            topic: topic.to_string(),
-        }
+        RbxMessaging::new(&self.api_key, universe_id, topic)
     }
 
     pub fn datastore(&self, universe_id: UniverseId) -> RbxDataStore {
         RbxDataStore {
             api_key: self.api_key.clone(),
             universe_id,
+            schemas: vec![],
+            preflight: true,
+            cache: None,
+            validate_cached_versions: false,
         }
     }
 
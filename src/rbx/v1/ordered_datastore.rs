@@ -3,6 +3,11 @@
 //! Typically, these operations should be consumed through the `RbxExperience`
 //! struct, obtained through the `RbxCloud` struct.
 //!
+//! These functions target the `ordered-data-stores/v1` endpoints, which are
+//! being retired. `RbxOrderedDataStore` goes through
+//! `v2::ordered_datastore` instead.
+
+use std::{fmt, str::FromStr};
 
 use reqwest::Response;
 use serde::Serialize;
@@ -10,7 +15,7 @@ use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::json;
 
 use crate::rbx::v1::{PageSize, UniverseId};
-use crate::rbx::{error::Error, util::QueryString};
+use crate::rbx::{error::Error, util::QueryString, v2};
 
 pub struct OrderedListEntriesParams {
     pub api_key: String,
@@ -23,6 +28,153 @@ pub struct OrderedListEntriesParams {
     pub filter: Option<String>,
 }
 
+/// Enumeration direction of listed entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderBy {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl OrderBy {
+    /// The `order_by` query value. Ascending is the default and is omitted.
+    pub fn as_query(&self) -> Option<&'static str> {
+        match self {
+            Self::Ascending => None,
+            Self::Descending => Some("desc"),
+        }
+    }
+
+    /// The Cloud v2 `orderBy` query value. Ascending is the default and is
+    /// omitted.
+    pub fn as_v2_query(&self) -> Option<&'static str> {
+        match self {
+            Self::Ascending => None,
+            Self::Descending => Some("value desc"),
+        }
+    }
+}
+
+impl FromStr for OrderBy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "asc" | "ascending" => Ok(Self::Ascending),
+            "desc" | "descending" => Ok(Self::Descending),
+            other => Err(Error::OrderedFilterError(format!(
+                "unknown order '{other}' (expected 'asc' or 'desc')"
+            ))),
+        }
+    }
+}
+
+/// Range of entry values to list. Bounds are inclusive.
+///
+/// Formats as the Open Cloud filter grammar, and parses it back, rejecting
+/// anything the server would refuse:
+///
+/// ```rust
+/// use rbxcloud::rbx::v1::ordered_datastore::OrderedFilter;
+///
+/// let filter = OrderedFilter::new().min(10).max(50);
+/// assert_eq!(filter.to_string(), "entry >= 10 && entry <= 50");
+/// assert_eq!("entry >= 10 && entry <= 50".parse::<OrderedFilter>().unwrap(), filter);
+/// assert!("entry >= 10 and entry <= 50".parse::<OrderedFilter>().is_err());
+/// assert!(OrderedFilter::new().min(50).max(10).validate().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OrderedFilter {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+
+impl OrderedFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list entries with a value of at least `min`.
+    pub fn min(mut self, min: i64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Only list entries with a value of at most `max`.
+    pub fn max(mut self, max: i64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    /// Check that the range is not empty.
+    pub fn validate(&self) -> Result<(), Error> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(Error::OrderedFilterError(format!(
+                "minimum {min} is greater than maximum {max}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// The `filter` query value, or `None` if there are no bounds.
+    pub fn as_query(&self) -> Result<Option<String>, Error> {
+        self.validate()?;
+        Ok((!self.is_empty()).then(|| self.to_string()))
+    }
+}
+
+impl fmt::Display for OrderedFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "entry >= {min} && entry <= {max}"),
+            (Some(min), None) => write!(f, "entry >= {min}"),
+            (None, Some(max)) => write!(f, "entry <= {max}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl FromStr for OrderedFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |msg: String| Error::OrderedFilterError(format!("{msg} in filter '{s}'"));
+        let mut filter = Self::new();
+        if s.trim().is_empty() {
+            return Ok(filter);
+        }
+        for condition in s.split("&&") {
+            let tokens: Vec<&str> = condition.split_whitespace().collect();
+            let [field, op, value] = tokens[..] else {
+                return Err(err(format!(
+                    "expected 'entry >= N' or 'entry <= N', found '{}' (conditions are joined with '&&')",
+                    condition.trim()
+                )));
+            };
+            if field != "entry" {
+                return Err(err(format!("unknown field '{field}'")));
+            }
+            let value: i64 = value
+                .parse()
+                .map_err(|_| err(format!("'{value}' is not an integer")))?;
+            let bound = match op {
+                ">=" => &mut filter.min,
+                "<=" => &mut filter.max,
+                _ => return Err(err(format!("unsupported operator '{op}'"))),
+            };
+            if bound.replace(value).is_some() {
+                return Err(err(format!("duplicate '{op}' condition")));
+            }
+        }
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
 pub struct OrderedCreateEntryParams {
     pub api_key: String,
     pub universe_id: UniverseId,
@@ -66,6 +218,29 @@ pub struct OrderedListEntriesResponse {
     pub next_page_token: Option<String>,
 }
 
+impl From<v2::ordered_datastore::OrderedDataStoreEntry> for OrderedEntry {
+    fn from(entry: v2::ordered_datastore::OrderedDataStoreEntry) -> Self {
+        Self {
+            path: entry.path,
+            id: entry.id,
+            value: entry.value,
+        }
+    }
+}
+
+impl From<v2::ordered_datastore::OrderedDataStoreEntryList> for OrderedListEntriesResponse {
+    fn from(list: v2::ordered_datastore::OrderedDataStoreEntryList) -> Self {
+        Self {
+            entries: list
+                .ordered_data_store_entries
+                .into_iter()
+                .map(OrderedEntry::from)
+                .collect(),
+            next_page_token: list.next_page_token,
+        }
+    }
+}
+
 pub struct OrderedEntryParams {
     pub api_key: String,
     pub universe_id: UniverseId,
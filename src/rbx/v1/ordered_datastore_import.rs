@@ -0,0 +1,275 @@
+//! Bulk import of OrderedDataStore entries.
+//!
+//! Rows are read from CSV or NDJSON, then written concurrently through
+//! `update_entry` (replacing values, creating missing entries) or
+//! `increment_entry` (adding to existing values). Rows that cannot be parsed
+//! or written are recorded in the report with their line number.
+//!
+//! CSV files have an `id` and a `value` column. A header row naming them is
+//! optional (without one, the first two columns are used), so files written
+//! by `export_leaderboard` can be imported as they are. NDJSON lines are
+//! objects with `id` and `value` fields.
+use std::{io::BufRead, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::rbx::{
+    error::Error,
+    util::{map_concurrent, Throttle},
+};
+
+use super::{OrderedDataStoreIncrementEntry, OrderedDataStoreUpdateEntry, RbxOrderedDataStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderedImportFormat {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderedImportMode {
+    /// Set each entry to the imported value, creating missing entries.
+    Upsert,
+    /// Add the imported value to each entry.
+    Increment,
+}
+
+pub struct OrderedDataStoreImportEntries {
+    pub name: String,
+    pub scope: Option<String>,
+    pub mode: OrderedImportMode,
+    /// Maximum number of entries written at once.
+    pub concurrency: usize,
+    /// Maximum number of requests sent per minute (0 for no limit).
+    pub max_requests_per_minute: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedImportRow {
+    /// Line of the row in the input, starting at 1.
+    pub line: u64,
+    pub id: String,
+    pub value: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedImportFailure {
+    pub line: u64,
+    pub id: Option<String>,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedDataStoreImportReport {
+    pub imported: u64,
+    /// Rows that could not be parsed.
+    pub invalid: u64,
+    /// Rows that could not be written.
+    pub failed: u64,
+    pub failures: Vec<OrderedImportFailure>,
+}
+
+/// Split a CSV line into fields, handling quoted fields.
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn parse_value(value: &str) -> Result<i64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("value '{}' is not an integer", value.trim()))
+}
+
+/// The `id` and `value` columns, if `fields` is a header row.
+fn header_columns(fields: &[String]) -> Option<(usize, usize)> {
+    let find = |name: &str| {
+        fields
+            .iter()
+            .position(|f| f.trim().eq_ignore_ascii_case(name))
+    };
+    Some((find("id")?, find("value")?))
+}
+
+fn parse_csv_row(
+    fields: &[String],
+    id_column: usize,
+    value_column: usize,
+) -> Result<(String, i64), (Option<String>, String)> {
+    let (Some(id), Some(value)) = (fields.get(id_column), fields.get(value_column)) else {
+        let expected = id_column.max(value_column) + 1;
+        return Err((None, format!("expected at least {expected} columns")));
+    };
+    let value = parse_value(value).map_err(|err| (Some(id.clone()), err))?;
+    Ok((id.clone(), value))
+}
+
+fn parse_ndjson_row(line: &str) -> Result<(String, i64), (Option<String>, String)> {
+    let row: Value = serde_json::from_str(line).map_err(|err| (None, err.to_string()))?;
+    let id = match row.get("id") {
+        Some(Value::String(id)) => id.clone(),
+        Some(Value::Number(id)) => id.to_string(),
+        _ => return Err((None, "missing string or number 'id'".to_string())),
+    };
+    match row.get("value").and_then(Value::as_i64) {
+        Some(value) => Ok((id, value)),
+        None => Err((Some(id), "missing integer 'value'".to_string())),
+    }
+}
+
+/// Read import rows. Rows that cannot be parsed are returned as failures;
+/// only I/O errors fail the whole read.
+pub fn read_import_rows<R: BufRead>(
+    reader: R,
+    format: OrderedImportFormat,
+) -> Result<Vec<Result<OrderedImportRow, OrderedImportFailure>>, Error> {
+    let mut rows = vec![];
+    let mut columns: Option<(usize, usize)> = None;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = i as u64 + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = match format {
+            OrderedImportFormat::Ndjson => parse_ndjson_row(&line),
+            OrderedImportFormat::Csv => match split_csv_line(&line) {
+                Err(error) => Err((None, error)),
+                Ok(fields) => {
+                    if columns.is_none() {
+                        if let Some(header) = header_columns(&fields) {
+                            columns = Some(header);
+                            continue;
+                        }
+                    }
+                    let (id_column, value_column) = *columns.get_or_insert((0, 1));
+                    parse_csv_row(&fields, id_column, value_column)
+                }
+            },
+        };
+        rows.push(match parsed {
+            Ok((id, _)) if id.is_empty() => Err(OrderedImportFailure {
+                line: line_number,
+                id: None,
+                error: "empty id".to_string(),
+            }),
+            Ok((id, value)) => Ok(OrderedImportRow {
+                line: line_number,
+                id,
+                value,
+            }),
+            Err((id, error)) => Err(OrderedImportFailure {
+                line: line_number,
+                id,
+                error,
+            }),
+        });
+    }
+    Ok(rows)
+}
+
+async fn import_row(
+    ordered_datastore: &RbxOrderedDataStore,
+    params: &OrderedDataStoreImportEntries,
+    row: &OrderedImportRow,
+) -> Result<(), Error> {
+    match params.mode {
+        OrderedImportMode::Upsert => {
+            ordered_datastore
+                .update_entry(&OrderedDataStoreUpdateEntry {
+                    name: params.name.clone(),
+                    scope: params.scope.clone(),
+                    id: row.id.clone(),
+                    value: row.value,
+                    allow_missing: Some(true),
+                })
+                .await?
+        }
+        OrderedImportMode::Increment => {
+            ordered_datastore
+                .increment_entry(&OrderedDataStoreIncrementEntry {
+                    name: params.name.clone(),
+                    scope: params.scope.clone(),
+                    id: row.id.clone(),
+                    increment: row.value,
+                })
+                .await?
+        }
+    };
+    Ok(())
+}
+
+/// Write rows (e.g. from `read_import_rows`) into an OrderedDataStore.
+///
+/// Invalid rows and failed writes are recorded in the report.
+pub async fn import_entries(
+    ordered_datastore: &RbxOrderedDataStore,
+    params: OrderedDataStoreImportEntries,
+    rows: Vec<Result<OrderedImportRow, OrderedImportFailure>>,
+) -> Result<OrderedDataStoreImportReport, Error> {
+    let mut report = OrderedDataStoreImportReport::default();
+    let mut valid = vec![];
+    for row in rows {
+        match row {
+            Ok(row) => valid.push(row),
+            Err(failure) => {
+                report.invalid += 1;
+                report.failures.push(failure);
+            }
+        }
+    }
+    let throttle = Mutex::new(Throttle::new(params.max_requests_per_minute));
+    let concurrency = params.concurrency;
+    let shared = Arc::new((ordered_datastore.clone(), params, throttle));
+    let results = map_concurrent(valid, concurrency, |row| {
+        let shared = shared.clone();
+        async move {
+            let (ordered_datastore, params, throttle) = shared.as_ref();
+            throttle.lock().await.wait().await;
+            let res = import_row(ordered_datastore, params, &row).await;
+            res.map_err(|err| OrderedImportFailure {
+                line: row.line,
+                id: Some(row.id),
+                error: err.to_string(),
+            })
+        }
+    })
+    .await;
+    for result in results {
+        match result {
+            Ok(()) => report.imported += 1,
+            Err(failure) => {
+                report.failed += 1;
+                report.failures.push(failure);
+            }
+        }
+    }
+    report.failures.sort_by_key(|f| f.line);
+    Ok(report)
+}
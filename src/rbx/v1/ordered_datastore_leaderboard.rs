@@ -0,0 +1,327 @@
+//! Leaderboards on top of OrderedDataStores.
+//!
+//! Ranks are competition ranks: entries with the same value share a rank,
+//! and the next rank skips accordingly (1, 2, 2, 4). Open Cloud has no rank
+//! endpoint, so ranks are computed by listing entries with range filters.
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::error::Error;
+
+use super::{
+    ordered_datastore::{OrderBy, OrderedEntry, OrderedFilter},
+    OrderedDataStoreEntry, OrderedDataStoreEntryPager, OrderedDataStoreListEntries, PageSize,
+    RbxOrderedDataStore,
+};
+
+pub struct OrderedDataStoreLeaderboard {
+    pub name: String,
+    pub scope: Option<String>,
+    /// Order in which entries are ranked: `Descending` when higher values
+    /// rank first.
+    pub order_by: OrderBy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedEntry {
+    pub rank: u64,
+    pub id: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardFormat {
+    /// `rank,id,value` rows with a header.
+    Csv,
+    /// A JSON array of `RankedEntry`.
+    Json,
+}
+
+const PAGE_SIZE: u64 = 100;
+
+fn reversed(order_by: OrderBy) -> OrderBy {
+    match order_by {
+        OrderBy::Ascending => OrderBy::Descending,
+        OrderBy::Descending => OrderBy::Ascending,
+    }
+}
+
+/// Values ranked strictly above `value`, or `None` if there are none.
+fn above_filter(order_by: OrderBy, value: i64) -> Option<OrderedFilter> {
+    match order_by {
+        OrderBy::Descending => value.checked_add(1).map(|v| OrderedFilter::new().min(v)),
+        OrderBy::Ascending => value.checked_sub(1).map(|v| OrderedFilter::new().max(v)),
+    }
+}
+
+/// Values ranked the same as or below `value`.
+fn at_or_below_filter(order_by: OrderBy, value: i64) -> OrderedFilter {
+    match order_by {
+        OrderBy::Descending => OrderedFilter::new().max(value),
+        OrderBy::Ascending => OrderedFilter::new().min(value),
+    }
+}
+
+fn pager(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    order_by: OrderBy,
+    filter: Option<OrderedFilter>,
+    page_size: u64,
+) -> OrderedDataStoreEntryPager {
+    ordered_datastore.entry_pager(OrderedDataStoreListEntries {
+        name: leaderboard.name.clone(),
+        scope: leaderboard.scope.clone(),
+        max_page_size: Some(PageSize(page_size.clamp(1, PAGE_SIZE))),
+        page_token: None,
+        order_by: Some(order_by),
+        filter,
+    })
+}
+
+/// Ranks entries read in leaderboard order from the top.
+#[derive(Default)]
+struct Ranker {
+    position: u64,
+    rank: u64,
+    last_value: Option<i64>,
+}
+
+impl Ranker {
+    fn rank(&mut self, entry: OrderedEntry) -> RankedEntry {
+        self.position += 1;
+        if self.last_value != Some(entry.value) {
+            self.rank = self.position;
+            self.last_value = Some(entry.value);
+        }
+        RankedEntry {
+            rank: self.rank,
+            id: entry.id,
+            value: entry.value,
+        }
+    }
+}
+
+async fn get_entry(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    id: &str,
+) -> Result<OrderedEntry, Error> {
+    ordered_datastore
+        .get_entry(&OrderedDataStoreEntry {
+            name: leaderboard.name.clone(),
+            scope: leaderboard.scope.clone(),
+            id: id.to_string(),
+        })
+        .await
+}
+
+/// Count the entries ranked above `value`, keeping the `k` closest to it
+/// (plus any tied with the last of those), closest first.
+async fn scan_above(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    value: i64,
+    k: usize,
+) -> Result<(u64, Vec<OrderedEntry>), Error> {
+    let Some(filter) = above_filter(leaderboard.order_by, value) else {
+        return Ok((0, vec![]));
+    };
+    let order_by = reversed(leaderboard.order_by);
+    let mut pager = pager(
+        ordered_datastore,
+        leaderboard,
+        order_by,
+        Some(filter),
+        PAGE_SIZE,
+    );
+    let mut count = 0;
+    let mut closest: Vec<OrderedEntry> = vec![];
+    while let Some(page) = pager.next_page().await? {
+        count += page.len() as u64;
+        for entry in page {
+            if closest.len() < k || closest.last().is_some_and(|e| e.value == entry.value) {
+                closest.push(entry);
+            }
+        }
+    }
+    Ok((count, closest))
+}
+
+/// Get the first `n` entries of a leaderboard.
+pub async fn top_n(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    n: usize,
+) -> Result<Vec<RankedEntry>, Error> {
+    let mut pager = pager(
+        ordered_datastore,
+        leaderboard,
+        leaderboard.order_by,
+        None,
+        n as u64,
+    );
+    let mut ranker = Ranker::default();
+    let mut top = vec![];
+    while top.len() < n {
+        let Some(page) = pager.next_page().await? else {
+            break;
+        };
+        let remaining = n - top.len();
+        top.extend(page.into_iter().take(remaining).map(|e| ranker.rank(e)));
+    }
+    Ok(top)
+}
+
+/// Get the rank of an entry.
+pub async fn rank_of(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    id: &str,
+) -> Result<RankedEntry, Error> {
+    let entry = get_entry(ordered_datastore, leaderboard, id).await?;
+    let (above, _) = scan_above(ordered_datastore, leaderboard, entry.value, 0).await?;
+    Ok(RankedEntry {
+        rank: above + 1,
+        id: entry.id,
+        value: entry.value,
+    })
+}
+
+/// Get an entry with up to `k` entries ranked right above it and `k` right
+/// below it, in leaderboard order. Entries tied with the entry are listed
+/// below it.
+pub async fn around(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    id: &str,
+    k: usize,
+) -> Result<Vec<RankedEntry>, Error> {
+    let entry = get_entry(ordered_datastore, leaderboard, id).await?;
+    let value = entry.value;
+    let (above_count, closest) = scan_above(ordered_datastore, leaderboard, value, k).await?;
+
+    // An entry above ranks after every entry above it, i.e. all but those
+    // between it (and its ties) and the target.
+    let mut result: Vec<RankedEntry> = closest
+        .iter()
+        .take(k)
+        .map(|e| {
+            let through = closest
+                .iter()
+                .rposition(|o| o.value == e.value)
+                .unwrap_or(0)
+                + 1;
+            RankedEntry {
+                rank: above_count - through as u64 + 1,
+                id: e.id.clone(),
+                value: e.value,
+            }
+        })
+        .collect();
+    result.reverse();
+    result.push(RankedEntry {
+        rank: above_count + 1,
+        id: entry.id.clone(),
+        value,
+    });
+
+    let mut below: Vec<OrderedEntry> = vec![];
+    let filter = at_or_below_filter(leaderboard.order_by, value);
+    let mut pager = pager(
+        ordered_datastore,
+        leaderboard,
+        leaderboard.order_by,
+        Some(filter),
+        k as u64 + 1,
+    );
+    while below.len() < k {
+        let Some(page) = pager.next_page().await? else {
+            break;
+        };
+        below.extend(page.into_iter().filter(|e| e.id != entry.id));
+    }
+    below.truncate(k);
+    for (i, e) in below.iter().enumerate() {
+        // Ties with the target share its rank; others rank after the
+        // target and every listed entry before their own ties.
+        let rank = if e.value == value {
+            above_count + 1
+        } else {
+            let first = below[..i]
+                .iter()
+                .position(|o| o.value == e.value)
+                .unwrap_or(i);
+            above_count + 2 + first as u64
+        };
+        result.push(RankedEntry {
+            rank,
+            id: e.id.clone(),
+            value: e.value,
+        });
+    }
+    Ok(result)
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Write the leaderboard, or its first `limit` entries, into `writer`.
+/// Returns the number of written entries.
+pub async fn export_leaderboard<W: Write>(
+    ordered_datastore: &RbxOrderedDataStore,
+    leaderboard: &OrderedDataStoreLeaderboard,
+    limit: Option<usize>,
+    format: LeaderboardFormat,
+    writer: &mut W,
+) -> Result<u64, Error> {
+    let page_size = limit.map_or(PAGE_SIZE, |limit| limit as u64);
+    let mut pager = pager(
+        ordered_datastore,
+        leaderboard,
+        leaderboard.order_by,
+        None,
+        page_size,
+    );
+    let mut ranker = Ranker::default();
+    let mut count = 0;
+    match format {
+        LeaderboardFormat::Csv => writeln!(writer, "rank,id,value")?,
+        LeaderboardFormat::Json => write!(writer, "[")?,
+    }
+    'pages: while let Some(page) = pager.next_page().await? {
+        for entry in page {
+            if limit.is_some_and(|limit| count >= limit as u64) {
+                break 'pages;
+            }
+            let ranked = ranker.rank(entry);
+            match format {
+                LeaderboardFormat::Csv => writeln!(
+                    writer,
+                    "{},{},{}",
+                    ranked.rank,
+                    csv_field(&ranked.id),
+                    ranked.value
+                )?,
+                LeaderboardFormat::Json => {
+                    if count > 0 {
+                        write!(writer, ",")?;
+                    }
+                    write!(writer, "\n  {}", serde_json::to_string(&ranked)?)?;
+                }
+            }
+            count += 1;
+        }
+    }
+    if format == LeaderboardFormat::Json {
+        writeln!(writer, "\n]")?;
+    }
+    Ok(count)
+}
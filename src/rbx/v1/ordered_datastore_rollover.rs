@@ -0,0 +1,424 @@
+//! Season rollover for OrderedDataStore leaderboards.
+//!
+//! A rollover runs in three phases:
+//!
+//! 1. Archive: every source entry is created in the archive. The source is
+//!    not modified, so paging through it is stable.
+//! 2. Verify: the archive is counted and compared with the source.
+//! 3. Reset: the source entries are deleted or set to zero, paging through
+//!    the archive.
+//!
+//! The state after every page is passed to the progress callback. Saving it
+//! and passing it back as `resume` continues an interrupted rollover where it
+//! stopped, and retries failed entries; every step is safe to repeat.
+//!
+//! Run a rollover while the game is not writing to the leaderboard, or the
+//! verification may fail.
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::{error::Error, util::map_concurrent};
+
+use super::{
+    ordered_datastore::{OrderedEntry, OrderedListEntriesResponse},
+    OrderedDataStoreCreateEntry, OrderedDataStoreEntry, OrderedDataStoreListEntries,
+    OrderedDataStoreUpdateEntry, PageSize, RbxOrderedDataStore,
+};
+
+/// Called with the rollover state after every page.
+pub type RolloverProgress = Arc<dyn Fn(&RolloverState) + Send + Sync>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RolloverReset {
+    /// Delete the source entries.
+    Delete,
+    /// Set the source entries to zero.
+    Zero,
+}
+
+pub struct OrderedDataStoreRollover {
+    pub name: String,
+    pub scope: Option<String>,
+    /// Archive name (defaults to the source name).
+    pub archive_name: Option<String>,
+    /// Archive scope (defaults to the source scope).
+    pub archive_scope: Option<String>,
+    pub reset: RolloverReset,
+    /// Maximum number of entries written at once.
+    pub concurrency: usize,
+    /// State of an interrupted rollover to continue.
+    pub resume: Option<RolloverState>,
+    pub progress: Option<RolloverProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RolloverPhase {
+    Archive,
+    Verify,
+    Reset,
+    Done,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverFailure {
+    pub id: String,
+    pub phase: RolloverPhase,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverState {
+    pub name: String,
+    pub scope: String,
+    pub archive_name: String,
+    pub archive_scope: String,
+    pub reset_mode: RolloverReset,
+    pub phase: RolloverPhase,
+    /// Token of the next page to process in the current phase.
+    pub page_token: Option<String>,
+    /// Source entries read.
+    pub source_count: u64,
+    /// Source entries present in the archive, including those archived by
+    /// an earlier run.
+    pub archived: u64,
+    /// Entries counted in the archive by the last verification.
+    pub archive_count: Option<u64>,
+    pub verified: Option<bool>,
+    /// Source entries deleted or zeroed.
+    pub reset: u64,
+    pub failures: Vec<RolloverFailure>,
+}
+
+const PAGE_SIZE: PageSize = PageSize(100);
+
+fn rollover_err(msg: impl Into<String>) -> Error {
+    Error::RolloverError(msg.into())
+}
+
+struct Rollover {
+    ordered_datastore: RbxOrderedDataStore,
+    state: RolloverState,
+    concurrency: usize,
+    progress: Option<RolloverProgress>,
+}
+
+impl Rollover {
+    fn report_progress(&self) {
+        if let Some(progress) = &self.progress {
+            progress(&self.state);
+        }
+    }
+
+    async fn list_page(
+        &self,
+        name: &str,
+        scope: &str,
+        page_size: PageSize,
+        page_token: Option<String>,
+    ) -> Result<OrderedListEntriesResponse, Error> {
+        self.ordered_datastore
+            .list_entries(&OrderedDataStoreListEntries {
+                name: name.to_string(),
+                scope: Some(scope.to_string()),
+                max_page_size: Some(page_size),
+                page_token,
+                order_by: None,
+                filter: None,
+            })
+            .await
+    }
+
+    /// Create an entry in the archive. An entry that already exists with the
+    /// same value was archived by an earlier run.
+    async fn archive_entry(
+        ordered_datastore: RbxOrderedDataStore,
+        name: String,
+        scope: String,
+        entry: OrderedEntry,
+    ) -> Result<(), String> {
+        let created = ordered_datastore
+            .create_entry(&OrderedDataStoreCreateEntry {
+                name: name.clone(),
+                scope: Some(scope.clone()),
+                id: entry.id.clone(),
+                value: entry.value,
+            })
+            .await;
+        let Err(err) = created else {
+            return Ok(());
+        };
+        let existing = ordered_datastore
+            .get_entry(&OrderedDataStoreEntry {
+                name,
+                scope: Some(scope),
+                id: entry.id,
+            })
+            .await;
+        match existing {
+            Ok(existing) if existing.value == entry.value => Ok(()),
+            Ok(existing) => Err(format!(
+                "archive already has value {} instead of {}",
+                existing.value, entry.value
+            )),
+            Err(_) => Err(err.to_string()),
+        }
+    }
+
+    async fn reset_entry(
+        ordered_datastore: RbxOrderedDataStore,
+        name: String,
+        scope: String,
+        id: String,
+        mode: RolloverReset,
+    ) -> Result<(), String> {
+        let res = match mode {
+            RolloverReset::Delete => {
+                ordered_datastore
+                    .delete_entry(&OrderedDataStoreEntry {
+                        name,
+                        scope: Some(scope),
+                        id,
+                    })
+                    .await
+            }
+            RolloverReset::Zero => ordered_datastore
+                .update_entry(&OrderedDataStoreUpdateEntry {
+                    name,
+                    scope: Some(scope),
+                    id,
+                    value: 0,
+                    allow_missing: Some(false),
+                })
+                .await
+                .map(|_| ()),
+        };
+        match res {
+            // Deleted by an earlier run, or by the game.
+            Err(Error::HttpStatusError { code: 404, .. }) => Ok(()),
+            res => res.map_err(|err| err.to_string()),
+        }
+    }
+
+    async fn archive(&mut self) -> Result<(), Error> {
+        while self.state.phase == RolloverPhase::Archive {
+            let state = &self.state;
+            let res = self
+                .list_page(
+                    &state.name,
+                    &state.scope,
+                    PAGE_SIZE,
+                    state.page_token.clone(),
+                )
+                .await?;
+            self.state.source_count += res.entries.len() as u64;
+            let ids: Vec<String> = res.entries.iter().map(|e| e.id.clone()).collect();
+            let (ordered_datastore, name, scope) = (
+                self.ordered_datastore.clone(),
+                self.state.archive_name.clone(),
+                self.state.archive_scope.clone(),
+            );
+            let results = map_concurrent(res.entries, self.concurrency, |entry| {
+                Self::archive_entry(
+                    ordered_datastore.clone(),
+                    name.clone(),
+                    scope.clone(),
+                    entry,
+                )
+            })
+            .await;
+            for (id, result) in ids.into_iter().zip(results) {
+                match result {
+                    Ok(()) => self.state.archived += 1,
+                    Err(error) => self.state.failures.push(RolloverFailure {
+                        id,
+                        phase: RolloverPhase::Archive,
+                        error,
+                    }),
+                }
+            }
+            self.state.page_token = res.next_page_token.filter(|t| !t.is_empty());
+            if self.state.page_token.is_none() {
+                self.state.phase = RolloverPhase::Verify;
+            }
+            self.report_progress();
+        }
+        Ok(())
+    }
+
+    async fn count(&self, name: &str, scope: &str) -> Result<u64, Error> {
+        let mut count = 0;
+        let mut page_token = None;
+        loop {
+            let res = self.list_page(name, scope, PAGE_SIZE, page_token).await?;
+            count += res.entries.len() as u64;
+            page_token = res.next_page_token.filter(|t| !t.is_empty());
+            if page_token.is_none() {
+                return Ok(count);
+            }
+        }
+    }
+
+    /// Compare the archive with the source. On a mismatch the archive phase
+    /// is started over, so resuming retries the missing entries.
+    async fn verify(&mut self) -> Result<(), Error> {
+        let archive_count = self
+            .count(&self.state.archive_name, &self.state.archive_scope)
+            .await?;
+        let verified = archive_count == self.state.source_count
+            && self.state.archived == self.state.source_count;
+        self.state.archive_count = Some(archive_count);
+        self.state.verified = Some(verified);
+        self.state.phase = if verified {
+            RolloverPhase::Reset
+        } else {
+            self.state.source_count = 0;
+            self.state.archived = 0;
+            RolloverPhase::Archive
+        };
+        self.report_progress();
+        Ok(())
+    }
+
+    async fn reset_ids(&mut self, ids: Vec<String>) {
+        let (ordered_datastore, name, scope, mode) = (
+            self.ordered_datastore.clone(),
+            self.state.name.clone(),
+            self.state.scope.clone(),
+            self.state.reset_mode,
+        );
+        let results = map_concurrent(ids.clone(), self.concurrency, |id| {
+            Self::reset_entry(
+                ordered_datastore.clone(),
+                name.clone(),
+                scope.clone(),
+                id,
+                mode,
+            )
+        })
+        .await;
+        for (id, result) in ids.into_iter().zip(results) {
+            match result {
+                Ok(()) => self.state.reset += 1,
+                Err(error) => self.state.failures.push(RolloverFailure {
+                    id,
+                    phase: RolloverPhase::Reset,
+                    error,
+                }),
+            }
+        }
+    }
+
+    async fn reset(&mut self) -> Result<(), Error> {
+        while self.state.phase == RolloverPhase::Reset {
+            let state = &self.state;
+            let res = self
+                .list_page(
+                    &state.archive_name,
+                    &state.archive_scope,
+                    PAGE_SIZE,
+                    state.page_token.clone(),
+                )
+                .await?;
+            self.reset_ids(res.entries.into_iter().map(|e| e.id).collect())
+                .await;
+            self.state.page_token = res.next_page_token.filter(|t| !t.is_empty());
+            if self.state.page_token.is_none() {
+                self.state.phase = RolloverPhase::Done;
+            }
+            self.report_progress();
+        }
+        Ok(())
+    }
+}
+
+/// Archive a leaderboard and reset it.
+///
+/// Returns the final state. If the archive does not match the source after
+/// archiving, the source is left untouched, `verified` is `false` and the
+/// returned state can be resumed to retry.
+pub async fn rollover(
+    ordered_datastore: &RbxOrderedDataStore,
+    params: OrderedDataStoreRollover,
+) -> Result<RolloverState, Error> {
+    let scope = params.scope.unwrap_or_else(|| "global".to_string());
+    let archive_name = params.archive_name.unwrap_or_else(|| params.name.clone());
+    let archive_scope = params.archive_scope.unwrap_or_else(|| scope.clone());
+    if archive_name == params.name && archive_scope == scope {
+        return Err(rollover_err("the archive must differ from the source"));
+    }
+    let mut rollover = Rollover {
+        ordered_datastore: ordered_datastore.clone(),
+        state: RolloverState {
+            name: params.name,
+            scope,
+            archive_name,
+            archive_scope,
+            reset_mode: params.reset,
+            phase: RolloverPhase::Archive,
+            page_token: None,
+            source_count: 0,
+            archived: 0,
+            archive_count: None,
+            verified: None,
+            reset: 0,
+            failures: vec![],
+        },
+        concurrency: params.concurrency,
+        progress: params.progress,
+    };
+    match params.resume {
+        Some(resume) => {
+            let state = &rollover.state;
+            if (
+                &resume.name,
+                &resume.scope,
+                &resume.archive_name,
+                &resume.archive_scope,
+            ) != (
+                &state.name,
+                &state.scope,
+                &state.archive_name,
+                &state.archive_scope,
+            ) {
+                return Err(rollover_err(
+                    "the resumed state is for a different source or archive",
+                ));
+            }
+            // Archive failures are retried by starting the archive phase
+            // over after a failed verification; reset failures are retried
+            // here.
+            let retry = resume
+                .failures
+                .iter()
+                .filter(|f| f.phase == RolloverPhase::Reset)
+                .map(|f| f.id.clone())
+                .collect();
+            rollover.state = resume;
+            rollover.state.failures.clear();
+            rollover.reset_ids(retry).await;
+        }
+        None => {
+            let state = &rollover.state;
+            let res = rollover
+                .list_page(&state.archive_name, &state.archive_scope, PageSize(1), None)
+                .await?;
+            if !res.entries.is_empty() {
+                return Err(rollover_err(format!(
+                    "archive {}/{} is not empty",
+                    state.archive_name, state.archive_scope
+                )));
+            }
+        }
+    }
+    rollover.archive().await?;
+    if rollover.state.phase == RolloverPhase::Verify {
+        rollover.verify().await?;
+    }
+    rollover.reset().await?;
+    Ok(rollover.state)
+}
@@ -0,0 +1,398 @@
+//! Reading and editing ProfileService and ProfileStore profiles.
+//!
+//! Both libraries store a player's data inside an envelope:
+//!
+//! ```json
+//! {
+//!   "Data": {"Coins": 10},
+//!   "MetaData": {
+//!     "ProfileCreateTime": 1700000000,
+//!     "SessionLoadCount": 3,
+//!     "ActiveSession": [1234567890, "a1b2c3d4-..."],
+//!     "MetaTags": {},
+//!     "LastUpdate": 1700000300
+//!   },
+//!   "GlobalUpdates": [1, [[1, 1, false, {"Gift": 100}]]]
+//! }
+//! ```
+//!
+//! A profile with an `ActiveSession` is loaded by a game server, which will
+//! overwrite any outside edit at its next auto-save. Edits to such profiles
+//! are refused unless forced. Like ProfileService, a lock is assumed dead
+//! once the profile has not been saved for `DEAD_SESSION_LOCK_AGE`, since
+//! the server that held it shut down without releasing it. Global updates
+//! (ProfileService only) are delivered to the active session, so they can
+//! be pushed at any time.
+//!
+//! ```rust
+//! use rbxcloud::rbx::v1::profile_store::ProfileEnvelope;
+//! use serde_json::json;
+//!
+//! let mut profile = ProfileEnvelope::parse(
+//!     r#"{"Data":{"Coins":10},"MetaData":{"ActiveSession":null,"MetaTags":{}},"GlobalUpdates":[0,[]]}"#,
+//! ).unwrap();
+//! assert!(profile.active_session().is_none());
+//! profile.set_data(json!({"Coins": 20}));
+//! let id = profile.push_global_update(json!({"Gift": 100})).unwrap();
+//! assert_eq!(id, 1);
+//! ```
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::rbx::error::Error;
+
+use super::{
+    datastore::{self, EntryAttributes, GetEntryParams},
+    DataStoreGetEntry, DataStoreSetEntry, RbxDataStore, RobloxUserId,
+};
+
+/// How long after the last save of a profile its session lock is assumed
+/// dead (ProfileService's `AssumeDeadSessionLock`).
+pub const DEAD_SESSION_LOCK_AGE: Duration = Duration::from_secs(30 * 60);
+
+/// The game server that has a profile loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    pub place_id: Value,
+    pub job_id: Value,
+}
+
+impl std::fmt::Display for ActiveSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "place {} job {}", self.place_id, self.job_id)
+    }
+}
+
+/// A ProfileService global update.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalUpdate {
+    pub id: u64,
+    pub version: u64,
+    /// Locked updates have been received by the game and wait to be cleared.
+    pub locked: bool,
+    pub data: Value,
+}
+
+fn invalid(msg: impl Into<String>) -> Error {
+    Error::InvalidProfile(msg.into())
+}
+
+/// A profile value, with every field of the envelope preserved.
+#[derive(Debug, Clone)]
+pub struct ProfileEnvelope {
+    raw: Map<String, Value>,
+}
+
+impl ProfileEnvelope {
+    /// Parse a DataStore value as a profile. Fails if the value is not an
+    /// object with `Data` and `MetaData` fields.
+    pub fn parse(data: &str) -> Result<Self, Error> {
+        Self::from_value(serde_json::from_str(data)?)
+    }
+
+    pub fn from_value(value: Value) -> Result<Self, Error> {
+        let Value::Object(raw) = value else {
+            return Err(invalid("profile value is not a JSON object"));
+        };
+        if !raw.contains_key("Data") {
+            return Err(invalid("profile has no Data field"));
+        }
+        if !raw.get("MetaData").is_some_and(Value::is_object) {
+            return Err(invalid("profile has no MetaData object"));
+        }
+        Ok(Self { raw })
+    }
+
+    pub fn as_value(&self) -> Value {
+        Value::Object(self.raw.clone())
+    }
+
+    pub fn to_json_string(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(&self.raw)?)
+    }
+
+    /// The game data wrapped by the envelope.
+    pub fn data(&self) -> &Value {
+        &self.raw["Data"]
+    }
+
+    pub fn set_data(&mut self, data: Value) {
+        self.raw.insert("Data".to_string(), data);
+    }
+
+    pub fn meta_data(&self) -> &Map<String, Value> {
+        self.raw["MetaData"]
+            .as_object()
+            .expect("MetaData is checked when parsing")
+    }
+
+    fn meta_data_mut(&mut self) -> &mut Map<String, Value> {
+        self.raw
+            .get_mut("MetaData")
+            .and_then(Value::as_object_mut)
+            .expect("MetaData is checked when parsing")
+    }
+
+    pub fn meta_tags(&self) -> Option<&Map<String, Value>> {
+        self.meta_data().get("MetaTags").and_then(Value::as_object)
+    }
+
+    pub fn set_meta_tag(&mut self, key: &str, value: Value) {
+        let tags = self
+            .meta_data_mut()
+            .entry("MetaTags")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !tags.is_object() {
+            *tags = Value::Object(Map::new());
+        }
+        if let Value::Object(tags) = tags {
+            tags.insert(key.to_string(), value);
+        }
+    }
+
+    /// The server holding the session lock, if any. Both the ProfileService
+    /// (`[placeId, jobId]`) and ProfileStore (`[placeId, jobId, time]`)
+    /// formats are read.
+    pub fn active_session(&self) -> Option<ActiveSession> {
+        let session = self.meta_data().get("ActiveSession")?.as_array()?;
+        Some(ActiveSession {
+            place_id: session.first().cloned().unwrap_or(Value::Null),
+            job_id: session.get(1).cloned().unwrap_or(Value::Null),
+        })
+    }
+
+    pub fn session_load_count(&self) -> Option<u64> {
+        self.meta_data().get("SessionLoadCount")?.as_u64()
+    }
+
+    /// Unix time of the last save by a game server.
+    pub fn last_update(&self) -> Option<f64> {
+        self.meta_data().get("LastUpdate")?.as_f64()
+    }
+
+    /// Returns an error if a game server holds the session lock, whether or
+    /// not the lock is stale (see `StoredProfile::check_unlocked`).
+    pub fn check_unlocked(&self) -> Result<(), Error> {
+        match self.active_session() {
+            Some(session) => Err(Error::ProfileSessionLocked(session.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// The ProfileService global updates, or `None` if the profile has
+    /// none (e.g. ProfileStore profiles).
+    pub fn global_updates(&self) -> Result<Option<Vec<GlobalUpdate>>, Error> {
+        let Some(global) = self.raw.get("GlobalUpdates") else {
+            return Ok(None);
+        };
+        let list = global
+            .get(1)
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("GlobalUpdates is not [index, updates]"))?;
+        let updates = list
+            .iter()
+            .map(|update| {
+                let parts = update.as_array().filter(|p| p.len() == 4);
+                let parts = parts.ok_or_else(|| invalid("malformed global update"))?;
+                Ok(GlobalUpdate {
+                    id: parts[0].as_u64().unwrap_or_default(),
+                    version: parts[1].as_u64().unwrap_or_default(),
+                    locked: parts[2].as_bool().unwrap_or_default(),
+                    data: parts[3].clone(),
+                })
+            })
+            .collect::<Result<Vec<GlobalUpdate>, Error>>()?;
+        Ok(Some(updates))
+    }
+
+    /// Add an active global update, returning its ID. Profiles without
+    /// `GlobalUpdates` get an empty list first.
+    pub fn push_global_update(&mut self, data: Value) -> Result<u64, Error> {
+        let global = self
+            .raw
+            .entry("GlobalUpdates")
+            .or_insert_with(|| Value::Array(vec![Value::from(0), Value::Array(vec![])]));
+        let Some([index, updates]) = global.as_array_mut().map(Vec::as_mut_slice) else {
+            return Err(invalid("GlobalUpdates is not [index, updates]"));
+        };
+        let (Some(last), Some(updates)) = (index.as_u64(), updates.as_array_mut()) else {
+            return Err(invalid("GlobalUpdates is not [index, updates]"));
+        };
+        let id = last + 1;
+        *index = Value::from(id);
+        updates.push(Value::Array(vec![
+            Value::from(id),
+            Value::from(1),
+            Value::Bool(false),
+            data,
+        ]));
+        Ok(id)
+    }
+}
+
+/// A profile read from a DataStore, with the entry metadata needed to write
+/// it back.
+#[derive(Debug, Clone)]
+pub struct StoredProfile {
+    pub profile: ProfileEnvelope,
+    pub version: Option<String>,
+    /// When the entry was last written.
+    pub updated_time: Option<DateTime<Utc>>,
+    pub user_ids: Vec<RobloxUserId>,
+    pub attributes: Option<EntryAttributes>,
+}
+
+/// Summary of a profile, for inspection.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub version: Option<String>,
+    pub active_session: Option<ActiveSession>,
+    pub session_load_count: Option<u64>,
+    pub last_update: Option<f64>,
+    /// Whether the session lock is old enough to be assumed dead.
+    pub session_lock_stale: bool,
+    pub meta_tags: Option<Map<String, Value>>,
+    pub global_updates: Option<Vec<GlobalUpdate>>,
+    pub user_ids: Vec<RobloxUserId>,
+    pub data: Value,
+}
+
+impl StoredProfile {
+    pub fn summary(&self) -> Result<ProfileSummary, Error> {
+        Ok(ProfileSummary {
+            version: self.version.clone(),
+            active_session: self.profile.active_session(),
+            session_load_count: self.profile.session_load_count(),
+            last_update: self.profile.last_update(),
+            session_lock_stale: self.is_session_lock_stale(Utc::now()),
+            meta_tags: self.profile.meta_tags().cloned(),
+            global_updates: self.profile.global_updates()?,
+            user_ids: self.user_ids.clone(),
+            data: self.profile.data().clone(),
+        })
+    }
+
+    /// Latest save of the profile, from the entry's update time and the
+    /// profile's `LastUpdate`.
+    fn last_saved(&self) -> Option<DateTime<Utc>> {
+        let last_update = self
+            .profile
+            .last_update()
+            .and_then(|secs| DateTime::from_timestamp(secs as i64, 0));
+        self.updated_time.max(last_update)
+    }
+
+    /// Whether the profile has an active session that has not saved it for
+    /// `DEAD_SESSION_LOCK_AGE`.
+    pub fn is_session_lock_stale(&self, now: DateTime<Utc>) -> bool {
+        if self.profile.active_session().is_none() {
+            return false;
+        }
+        let Some(last_saved) = self.last_saved() else {
+            return false;
+        };
+        now.signed_duration_since(last_saved)
+            .to_std()
+            .is_ok_and(|age| age >= DEAD_SESSION_LOCK_AGE)
+    }
+
+    /// Returns an error if a game server holds the session lock and still
+    /// saves the profile. Stale locks are ignored.
+    pub fn check_unlocked(&self) -> Result<(), Error> {
+        if self.is_session_lock_stale(Utc::now()) {
+            return Ok(());
+        }
+        self.profile.check_unlocked()
+    }
+}
+
+/// Read a profile.
+pub async fn get_profile(
+    datastore: &RbxDataStore,
+    params: &DataStoreGetEntry,
+) -> Result<StoredProfile, Error> {
+    // Bypass the cache, so the version used to guard writes is current.
+    let entry = datastore::get_entry_with_metadata(&GetEntryParams {
+        api_key: datastore.api_key.clone(),
+        universe_id: datastore.universe_id,
+        datastore_name: params.name.clone(),
+        scope: params.scope.clone(),
+        key: params.key.clone(),
+    })
+    .await?;
+    Ok(StoredProfile {
+        profile: ProfileEnvelope::parse(&entry.data)?,
+        version: entry.metadata.version,
+        updated_time: entry
+            .metadata
+            .version_created_time
+            .as_deref()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc)),
+        user_ids: entry.metadata.user_ids,
+        attributes: entry.metadata.attributes,
+    })
+}
+
+/// Write a profile back, only if it was not modified since it was read.
+async fn put_profile(
+    datastore: &RbxDataStore,
+    params: &DataStoreGetEntry,
+    stored: &mut StoredProfile,
+) -> Result<(), Error> {
+    let user_ids = stored.user_ids.clone();
+    let res = datastore
+        .set_entry(&DataStoreSetEntry {
+            name: params.name.clone(),
+            scope: params.scope.clone(),
+            key: params.key.clone(),
+            match_version: stored.version.clone(),
+            exclusive_create: None,
+            roblox_entry_user_ids: (!user_ids.is_empty()).then_some(user_ids),
+            roblox_entry_attributes: stored.attributes.clone(),
+            data: stored.profile.to_json_string()?,
+        })
+        .await?;
+    stored.version = Some(res.version);
+    Ok(())
+}
+
+/// Edit a profile. Session-locked profiles are refused unless `force` is
+/// set or the lock is stale. The write is guarded by the version that was read, so concurrent
+/// saves by a game server make it fail instead of being overwritten.
+pub async fn update_profile<F>(
+    datastore: &RbxDataStore,
+    params: &DataStoreGetEntry,
+    force: bool,
+    edit: F,
+) -> Result<StoredProfile, Error>
+where
+    F: FnOnce(&mut ProfileEnvelope) -> Result<(), Error>,
+{
+    let mut stored = get_profile(datastore, params).await?;
+    if !force {
+        stored.check_unlocked()?;
+    }
+    edit(&mut stored.profile)?;
+    put_profile(datastore, params, &mut stored).await?;
+    Ok(stored)
+}
+
+/// Push a global update to a ProfileService profile, returning its ID. This
+/// is allowed while the profile is session-locked.
+pub async fn push_global_update(
+    datastore: &RbxDataStore,
+    params: &DataStoreGetEntry,
+    data: Value,
+) -> Result<u64, Error> {
+    let mut stored = get_profile(datastore, params).await?;
+    let id = stored.profile.push_global_update(data)?;
+    put_profile(datastore, params, &mut stored).await?;
+    Ok(id)
+}
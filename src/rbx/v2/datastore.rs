@@ -0,0 +1,518 @@
+//! Cloud v2 DataStore API operations.
+//!
+//! Typically, these operations should be consumed through the
+//! `DataStoreClient` struct, obtained through `Client::datastore()`.
+use reqwest::{Response, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::rbx::{
+    error::Error,
+    types::{RobloxUserId, UniverseId},
+    util::QueryString,
+    v1::datastore::EntryAttributes,
+};
+
+use super::http_err::handle_http_err_response;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreInfo {
+    pub path: String,
+    pub id: String,
+    pub create_time: Option<String>,
+    /// `ACTIVE` or `DELETED`.
+    pub state: Option<String>,
+    /// When a deleted DataStore will be permanently removed.
+    pub expire_time: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreList {
+    #[serde(default)]
+    pub data_stores: Vec<DataStoreInfo>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreEntry {
+    pub path: String,
+    pub id: String,
+    pub create_time: Option<String>,
+    pub revision_id: Option<String>,
+    pub revision_create_time: Option<String>,
+    pub state: Option<String>,
+    /// Used to make conditional updates (see `DataStoreEntryValue::etag`).
+    pub etag: Option<String>,
+    /// The entry value. `null` in listings, which only carry entry IDs.
+    #[serde(default)]
+    pub value: Value,
+    /// Associated users, as `users/{id}` paths.
+    #[serde(default)]
+    pub users: Vec<String>,
+    pub attributes: Option<EntryAttributes>,
+}
+
+impl DataStoreEntry {
+    /// The IDs of the users associated with the entry.
+    pub fn user_ids(&self) -> Vec<RobloxUserId> {
+        self.users
+            .iter()
+            .filter_map(|user| user.strip_prefix("users/")?.parse().ok())
+            .map(RobloxUserId)
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataStoreEntryList {
+    #[serde(default)]
+    pub data_store_entries: Vec<DataStoreEntry>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDataStoresResponse {
+    /// `false` if a snapshot had already been taken during the current UTC day.
+    pub new_snapshot_taken: bool,
+    pub latest_snapshot_time: Option<String>,
+}
+
+/// The value and metadata to write into an entry.
+#[derive(Debug, Clone, Default)]
+pub struct DataStoreEntryValue {
+    pub value: Value,
+    pub users: Vec<RobloxUserId>,
+    pub attributes: Option<EntryAttributes>,
+    /// Only update the entry if its current etag matches.
+    pub etag: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryBody<'a> {
+    value: &'a Value,
+    users: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<&'a EntryAttributes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    etag: Option<&'a String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IncrementBody<'a> {
+    amount: f64,
+    users: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<&'a EntryAttributes>,
+}
+
+pub struct ListDataStoresParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub max_page_size: Option<u32>,
+    pub page_token: Option<String>,
+    pub filter: Option<String>,
+    pub show_deleted: Option<bool>,
+}
+
+pub struct DataStoreParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+}
+
+pub struct SnapshotDataStoresParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+}
+
+pub struct ListDataStoreEntriesParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub max_page_size: Option<u32>,
+    pub page_token: Option<String>,
+    pub filter: Option<String>,
+    pub show_deleted: Option<bool>,
+}
+
+pub struct DataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+}
+
+pub struct GetDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    /// Get this revision instead of the latest one.
+    pub revision_id: Option<String>,
+}
+
+pub struct CreateDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub entry: DataStoreEntryValue,
+}
+
+pub struct UpdateDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub entry: DataStoreEntryValue,
+    /// Create the entry if it does not exist.
+    pub allow_missing: Option<bool>,
+}
+
+pub struct IncrementDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub amount: f64,
+    pub users: Vec<RobloxUserId>,
+    pub attributes: Option<EntryAttributes>,
+}
+
+pub struct ListDataStoreEntryRevisionsParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub max_page_size: Option<u32>,
+    pub page_token: Option<String>,
+    pub filter: Option<String>,
+}
+
+/// Build a URL under the universe, percent-encoding each path segment.
+fn build_url(universe_id: UniverseId, segments: &[&str]) -> Url {
+    let mut url = Url::parse(&format!(
+        "https://apis.roblox.com/cloud/v2/universes/{universe_id}"
+    ))
+    .expect("valid base url");
+    url.path_segments_mut()
+        .expect("base url has a path")
+        .extend(segments);
+    url
+}
+
+/// URL of the entries collection of a DataStore, or of the entry (plus an
+/// optional custom method, e.g. `:increment`) when `entry` is given.
+fn entry_url(
+    universe_id: UniverseId,
+    data_store_id: &str,
+    scope: &Option<String>,
+    entry: Option<&str>,
+) -> Url {
+    let mut segments = vec!["data-stores", data_store_id];
+    if let Some(scope) = scope {
+        segments.push("scopes");
+        segments.push(scope);
+    }
+    segments.push("entries");
+    if let Some(entry) = entry {
+        segments.push(entry);
+    }
+    build_url(universe_id, &segments)
+}
+
+fn user_paths(users: &[RobloxUserId]) -> Vec<String> {
+    users.iter().map(|id| format!("users/{id}")).collect()
+}
+
+fn page_query(
+    max_page_size: Option<u32>,
+    page_token: &Option<String>,
+    filter: &Option<String>,
+    show_deleted: Option<bool>,
+) -> QueryString {
+    let mut query: QueryString = vec![];
+    if let Some(max_page_size) = max_page_size {
+        query.push(("maxPageSize", max_page_size.to_string()));
+    }
+    if let Some(page_token) = page_token {
+        query.push(("pageToken", page_token.to_string()));
+    }
+    if let Some(filter) = filter {
+        query.push(("filter", filter.to_string()));
+    }
+    if let Some(show_deleted) = show_deleted {
+        query.push(("showDeleted", show_deleted.to_string()));
+    }
+    query
+}
+
+async fn handle_res<T: DeserializeOwned>(res: Response) -> Result<T, Error> {
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    let body = res.json::<T>().await?;
+    Ok(body)
+}
+
+/// List the DataStores of a universe.
+pub async fn list_data_stores(params: &ListDataStoresParams) -> Result<DataStoreList, Error> {
+    let client = reqwest::Client::new();
+    let url = build_url(params.universe_id, &["data-stores"]);
+    let query = page_query(
+        params.max_page_size,
+        &params.page_token,
+        &params.filter,
+        params.show_deleted,
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<DataStoreList>(res).await
+}
+
+/// Schedule a DataStore for deletion. It can be restored with
+/// `undelete_data_store` until its `expire_time`.
+pub async fn delete_data_store(params: &DataStoreParams) -> Result<DataStoreInfo, Error> {
+    let client = reqwest::Client::new();
+    let url = build_url(params.universe_id, &["data-stores", &params.data_store_id]);
+    let res = client
+        .delete(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    handle_res::<DataStoreInfo>(res).await
+}
+
+/// Restore a DataStore scheduled for deletion.
+pub async fn undelete_data_store(params: &DataStoreParams) -> Result<DataStoreInfo, Error> {
+    let client = reqwest::Client::new();
+    let url = build_url(
+        params.universe_id,
+        &["data-stores", &format!("{}:undelete", params.data_store_id)],
+    );
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body("{}")
+        .send()
+        .await?;
+    handle_res::<DataStoreInfo>(res).await
+}
+
+/// Take a snapshot of every DataStore in the universe. At most one snapshot
+/// is taken per UTC day.
+pub async fn snapshot_data_stores(
+    params: &SnapshotDataStoresParams,
+) -> Result<SnapshotDataStoresResponse, Error> {
+    let client = reqwest::Client::new();
+    let url = build_url(params.universe_id, &["data-stores:snapshot"]);
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body("{}")
+        .send()
+        .await?;
+    handle_res::<SnapshotDataStoresResponse>(res).await
+}
+
+/// List the entries of a DataStore.
+pub async fn list_data_store_entries(
+    params: &ListDataStoreEntriesParams,
+) -> Result<DataStoreEntryList, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        None,
+    );
+    let query = page_query(
+        params.max_page_size,
+        &params.page_token,
+        &params.filter,
+        params.show_deleted,
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntryList>(res).await
+}
+
+/// Get an entry, or one of its revisions.
+pub async fn get_data_store_entry(
+    params: &GetDataStoreEntryParams,
+) -> Result<DataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let entry = match &params.revision_id {
+        Some(revision_id) => format!("{}@{revision_id}", params.entry_id),
+        None => params.entry_id.clone(),
+    };
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        Some(&entry),
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntry>(res).await
+}
+
+/// Create an entry. Fails if the entry already exists.
+pub async fn create_data_store_entry(
+    params: &CreateDataStoreEntryParams,
+) -> Result<DataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        None,
+    );
+    let body = serde_json::to_string(&EntryBody {
+        value: &params.entry.value,
+        users: user_paths(&params.entry.users),
+        attributes: params.entry.attributes.as_ref(),
+        etag: None,
+    })?;
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&[("id", &params.entry_id)])
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntry>(res).await
+}
+
+/// Update an entry. When an etag is given, the update fails unless it
+/// matches the entry's current etag.
+pub async fn update_data_store_entry(
+    params: &UpdateDataStoreEntryParams,
+) -> Result<DataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        Some(&params.entry_id),
+    );
+    let mut query: QueryString = vec![];
+    if let Some(allow_missing) = params.allow_missing {
+        query.push(("allowMissing", allow_missing.to_string()));
+    }
+    let body = serde_json::to_string(&EntryBody {
+        value: &params.entry.value,
+        users: user_paths(&params.entry.users),
+        attributes: params.entry.attributes.as_ref(),
+        etag: params.entry.etag.as_ref(),
+    })?;
+    let res = client
+        .patch(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&query)
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntry>(res).await
+}
+
+/// Delete an entry.
+pub async fn delete_data_store_entry(params: &DataStoreEntryParams) -> Result<(), Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        Some(&params.entry_id),
+    );
+    let res = client
+        .delete(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    Ok(())
+}
+
+/// Increment the numeric value of an entry, creating it if it does not exist.
+pub async fn increment_data_store_entry(
+    params: &IncrementDataStoreEntryParams,
+) -> Result<DataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        Some(&format!("{}:increment", params.entry_id)),
+    );
+    let body = serde_json::to_string(&IncrementBody {
+        amount: params.amount,
+        users: user_paths(&params.users),
+        attributes: params.attributes.as_ref(),
+    })?;
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntry>(res).await
+}
+
+/// List the revisions of an entry, newest first.
+pub async fn list_data_store_entry_revisions(
+    params: &ListDataStoreEntryRevisionsParams,
+) -> Result<DataStoreEntryList, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.data_store_id,
+        &params.scope,
+        Some(&format!("{}:listRevisions", params.entry_id)),
+    );
+    let query = page_query(
+        params.max_page_size,
+        &params.page_token,
+        &params.filter,
+        None,
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<DataStoreEntryList>(res).await
+}
@@ -1,5 +1,15 @@
+use reqwest::Response;
+use serde::Deserialize;
+
 use crate::rbx::error::Error;
 
+/// Error body returned by the Cloud v2 APIs.
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: Option<String>,
+    message: Option<String>,
+}
+
 pub fn handle_http_err<T>(code: u16) -> Result<T, Error> {
     match code {
         400 => Err(Error::HttpStatusError {
@@ -44,3 +54,23 @@ pub fn handle_http_err<T>(code: u16) -> Result<T, Error> {
         }),
     }
 }
+
+/// Same as `handle_http_err()`, but uses the `{code, message}` error body of
+/// the response when there is one, so the reason given by Roblox is kept.
+pub async fn handle_http_err_response<T>(res: Response) -> Result<T, Error> {
+    let code = res.status().as_u16();
+    let body = res.text().await.unwrap_or_default();
+    match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(ErrorBody {
+            code: status,
+            message: Some(message),
+        }) if !message.is_empty() => Err(Error::HttpStatusError {
+            code,
+            msg: match status {
+                Some(status) => format!("{status}: {message}"),
+                None => message,
+            },
+        }),
+        _ => handle_http_err(code),
+    }
+}
@@ -0,0 +1,600 @@
+//! Cloud v2 MemoryStore API operations.
+//!
+//! Typically, these operations should be consumed through the
+//! `MemoryStoreClient` struct, obtained through `Client::memory_store()`.
+//!
+//! Every MemoryStore item expires. TTLs are sent as whole seconds and can be
+//! at most 45 days; `parse_ttl` reads them from strings such as `30s`,
+//! `10m`, `2h` or `1d`:
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use rbxcloud::rbx::v2::memory_store::{format_ttl, parse_ttl};
+//!
+//! assert_eq!(parse_ttl("10m").unwrap(), Duration::from_secs(600));
+//! assert_eq!(parse_ttl("1.5s").unwrap(), Duration::from_millis(1500));
+//! assert_eq!(format_ttl(Duration::from_secs(600)).unwrap(), "600s");
+//! assert!(format_ttl(Duration::from_secs(46 * 24 * 60 * 60)).is_err());
+//! ```
+use std::{fmt, time::Duration};
+
+use reqwest::{Response, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::rbx::{error::Error, types::UniverseId, util::QueryString};
+
+use super::http_err::handle_http_err_response;
+
+/// Longest TTL accepted for MemoryStore items (45 days).
+pub const MAX_TTL: Duration = Duration::from_secs(45 * 24 * 60 * 60);
+
+/// Sort key of a sorted map item. Items are sorted by sort key first, then
+/// by ID.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKey {
+    String(String),
+    Numeric(f64),
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::String(key) => write!(f, "{key:?}"),
+            Self::Numeric(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+/// Enumeration direction of listed sorted map items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortedMapOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortedMapOrder {
+    /// The `orderBy` query value. Ascending is the default and is omitted.
+    pub fn as_query(&self) -> Option<&'static str> {
+        match self {
+            Self::Ascending => None,
+            Self::Descending => Some("desc"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStoreSortedMapItem {
+    pub path: String,
+    pub id: String,
+    #[serde(default)]
+    pub value: Value,
+    /// Used to make conditional updates (see `SortedMapItemValue::etag`).
+    pub etag: Option<String>,
+    /// Time to live, e.g. `30s`.
+    pub ttl: Option<String>,
+    pub expire_time: Option<String>,
+    pub string_sort_key: Option<String>,
+    pub numeric_sort_key: Option<f64>,
+}
+
+impl MemoryStoreSortedMapItem {
+    pub fn sort_key(&self) -> Option<SortKey> {
+        match (&self.string_sort_key, self.numeric_sort_key) {
+            (Some(key), _) => Some(SortKey::String(key.clone())),
+            (None, Some(key)) => Some(SortKey::Numeric(key)),
+            (None, None) => None,
+        }
+    }
+
+    /// The item's time to live, if the server reported it.
+    pub fn ttl_duration(&self) -> Option<Duration> {
+        parse_ttl(self.ttl.as_deref()?).ok()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStoreSortedMapItemList {
+    #[serde(default)]
+    pub memory_store_sorted_map_items: Vec<MemoryStoreSortedMapItem>,
+    pub next_page_token: Option<String>,
+}
+
+/// The value, expiration and sort key to write into a sorted map item.
+#[derive(Debug, Clone)]
+pub struct SortedMapItemValue {
+    pub value: Value,
+    pub ttl: Duration,
+    pub sort_key: Option<SortKey>,
+    /// Only update the item if its current etag matches.
+    pub etag: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SortedMapItemBody<'a> {
+    value: &'a Value,
+    ttl: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    string_sort_key: Option<&'a String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    numeric_sort_key: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    etag: Option<&'a String>,
+}
+
+impl<'a> SortedMapItemBody<'a> {
+    fn new(item: &'a SortedMapItemValue) -> Result<Self, Error> {
+        let (string_sort_key, numeric_sort_key) = match &item.sort_key {
+            Some(SortKey::String(key)) => (Some(key), None),
+            Some(SortKey::Numeric(key)) => (None, Some(*key)),
+            None => (None, None),
+        };
+        Ok(Self {
+            value: &item.value,
+            ttl: format_ttl(item.ttl)?,
+            string_sort_key,
+            numeric_sort_key,
+            etag: item.etag.as_ref(),
+        })
+    }
+}
+
+pub struct ListSortedMapItemsParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub sorted_map_id: String,
+    pub max_page_size: Option<u32>,
+    pub page_token: Option<String>,
+    pub order_by: Option<SortedMapOrder>,
+    /// Bounds on IDs and sort keys, e.g. `id > "player_100" && sortKey < 50`.
+    pub filter: Option<String>,
+}
+
+pub struct SortedMapItemParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub sorted_map_id: String,
+    pub item_id: String,
+}
+
+pub struct CreateSortedMapItemParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub sorted_map_id: String,
+    pub item_id: String,
+    pub item: SortedMapItemValue,
+}
+
+pub struct UpdateSortedMapItemParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub sorted_map_id: String,
+    pub item_id: String,
+    pub item: SortedMapItemValue,
+    /// Create the item if it does not exist.
+    pub allow_missing: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStoreQueueItem {
+    /// Empty for items returned by `read_queue_items`.
+    #[serde(default)]
+    pub path: String,
+    #[serde(default)]
+    pub data: Value,
+    /// Items with a higher priority are read first.
+    #[serde(default)]
+    pub priority: f64,
+    pub ttl: Option<String>,
+    pub expire_time: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStoreQueueRead {
+    /// Identifies the read items, to discard them.
+    #[serde(default)]
+    pub read_id: String,
+    #[serde(default)]
+    pub items: Vec<MemoryStoreQueueItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnqueueBody<'a> {
+    data: &'a Value,
+    priority: f64,
+    ttl: String,
+}
+
+pub struct EnqueueItemParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub queue_id: String,
+    pub data: Value,
+    pub priority: f64,
+    pub ttl: Duration,
+}
+
+pub struct ReadQueueItemsParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub queue_id: String,
+    /// Number of items to read (1 by default).
+    pub count: Option<u32>,
+    /// Read nothing unless `count` items are available.
+    pub all_or_nothing: Option<bool>,
+    /// How long the read items are hidden from other readers (30 seconds by
+    /// default). Items that are not discarded within the window can be read
+    /// again.
+    pub invisibility_window: Option<Duration>,
+}
+
+pub struct DiscardQueueItemsParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub queue_id: String,
+    pub read_id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationError {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// A long-running flush operation.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStoreFlushOperation {
+    /// `universes/{universeId}/memory-store/operations/{operationId}`.
+    pub path: String,
+    #[serde(default)]
+    pub done: bool,
+    /// Set when the operation is done and failed.
+    pub error: Option<OperationError>,
+    pub response: Option<Value>,
+    pub metadata: Option<Value>,
+}
+
+impl MemoryStoreFlushOperation {
+    pub fn operation_id(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or_default()
+    }
+
+    /// `true` once the operation is done without an error.
+    pub fn succeeded(&self) -> bool {
+        self.done && self.error.is_none()
+    }
+}
+
+pub struct FlushMemoryStoreParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+}
+
+pub struct GetFlushOperationParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub operation_id: String,
+}
+
+fn memory_store_err(msg: impl Into<String>) -> Error {
+    Error::MemoryStoreError(msg.into())
+}
+
+/// Format a TTL for a request. Fails if it is shorter than a second or
+/// longer than `MAX_TTL`.
+pub fn format_ttl(ttl: Duration) -> Result<String, Error> {
+    if ttl < Duration::from_secs(1) {
+        return Err(memory_store_err("ttl must be at least 1 second"));
+    }
+    if ttl > MAX_TTL {
+        return Err(memory_store_err(format!(
+            "ttl of {}s exceeds the maximum of {}s (45 days)",
+            ttl.as_secs(),
+            MAX_TTL.as_secs()
+        )));
+    }
+    Ok(format!("{}s", ttl.as_secs()))
+}
+
+/// Parse a TTL such as `30s`, `1.5s`, `10m`, `2h` or `1d`. A number without
+/// a unit is read as seconds.
+pub fn parse_ttl(s: &str) -> Result<Duration, Error> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let seconds_per_unit = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 60.0 * 60.0,
+        "d" => 24.0 * 60.0 * 60.0,
+        _ => return Err(memory_store_err(format!("unknown ttl unit in '{s}'"))),
+    };
+    let number: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| memory_store_err(format!("invalid ttl '{s}'")))?;
+    Duration::try_from_secs_f64(number * seconds_per_unit)
+        .map_err(|_| memory_store_err(format!("invalid ttl '{s}'")))
+}
+
+/// Build a URL under the universe's MemoryStore, percent-encoding each path
+/// segment.
+fn memory_store_url(universe_id: UniverseId, segments: &[&str]) -> Url {
+    let mut url = Url::parse(&format!(
+        "https://apis.roblox.com/cloud/v2/universes/{universe_id}/memory-store"
+    ))
+    .expect("valid base url");
+    url.path_segments_mut()
+        .expect("base url has a path")
+        .extend(segments);
+    url
+}
+
+fn queue_url(universe_id: UniverseId, queue_id: &str, method: Option<&str>) -> Url {
+    let items = match method {
+        Some(method) => format!("items:{method}"),
+        None => "items".to_string(),
+    };
+    memory_store_url(universe_id, &["queues", queue_id, &items])
+}
+
+fn item_url(universe_id: UniverseId, sorted_map_id: &str, item_id: Option<&str>) -> Url {
+    let mut segments = vec!["sorted-maps", sorted_map_id, "items"];
+    if let Some(item_id) = item_id {
+        segments.push(item_id);
+    }
+    memory_store_url(universe_id, &segments)
+}
+
+async fn handle_res<T: DeserializeOwned>(res: Response) -> Result<T, Error> {
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    let body = res.json::<T>().await?;
+    Ok(body)
+}
+
+/// List the items of a sorted map.
+pub async fn list_sorted_map_items(
+    params: &ListSortedMapItemsParams,
+) -> Result<MemoryStoreSortedMapItemList, Error> {
+    let client = reqwest::Client::new();
+    let url = item_url(params.universe_id, &params.sorted_map_id, None);
+    let mut query: QueryString = vec![];
+    if let Some(max_page_size) = params.max_page_size {
+        query.push(("maxPageSize", max_page_size.to_string()));
+    }
+    if let Some(page_token) = &params.page_token {
+        query.push(("pageToken", page_token.to_string()));
+    }
+    if let Some(order_by) = params.order_by.and_then(|order_by| order_by.as_query()) {
+        query.push(("orderBy", order_by.to_string()));
+    }
+    if let Some(filter) = &params.filter {
+        query.push(("filter", filter.to_string()));
+    }
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreSortedMapItemList>(res).await
+}
+
+/// Get a sorted map item.
+pub async fn get_sorted_map_item(
+    params: &SortedMapItemParams,
+) -> Result<MemoryStoreSortedMapItem, Error> {
+    let client = reqwest::Client::new();
+    let url = item_url(
+        params.universe_id,
+        &params.sorted_map_id,
+        Some(&params.item_id),
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreSortedMapItem>(res).await
+}
+
+/// Create a sorted map item. Fails if the item already exists.
+pub async fn create_sorted_map_item(
+    params: &CreateSortedMapItemParams,
+) -> Result<MemoryStoreSortedMapItem, Error> {
+    let body = serde_json::to_string(&SortedMapItemBody::new(&params.item)?)?;
+    let client = reqwest::Client::new();
+    let url = item_url(params.universe_id, &params.sorted_map_id, None);
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&[("id", &params.item_id)])
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreSortedMapItem>(res).await
+}
+
+/// Update a sorted map item, replacing its value, TTL and sort key. When an
+/// etag is given, the update fails unless it matches the item's current
+/// etag.
+pub async fn update_sorted_map_item(
+    params: &UpdateSortedMapItemParams,
+) -> Result<MemoryStoreSortedMapItem, Error> {
+    let body = serde_json::to_string(&SortedMapItemBody::new(&params.item)?)?;
+    let client = reqwest::Client::new();
+    let url = item_url(
+        params.universe_id,
+        &params.sorted_map_id,
+        Some(&params.item_id),
+    );
+    let mut query: QueryString = vec![];
+    if let Some(allow_missing) = params.allow_missing {
+        query.push(("allowMissing", allow_missing.to_string()));
+    }
+    let res = client
+        .patch(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&query)
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreSortedMapItem>(res).await
+}
+
+/// Delete a sorted map item.
+pub async fn delete_sorted_map_item(params: &SortedMapItemParams) -> Result<(), Error> {
+    let client = reqwest::Client::new();
+    let url = item_url(
+        params.universe_id,
+        &params.sorted_map_id,
+        Some(&params.item_id),
+    );
+    let res = client
+        .delete(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    Ok(())
+}
+
+/// Add an item to a queue.
+pub async fn enqueue_item(params: &EnqueueItemParams) -> Result<MemoryStoreQueueItem, Error> {
+    let body = serde_json::to_string(&EnqueueBody {
+        data: &params.data,
+        priority: params.priority,
+        ttl: format_ttl(params.ttl)?,
+    })?;
+    let client = reqwest::Client::new();
+    let url = queue_url(params.universe_id, &params.queue_id, None);
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreQueueItem>(res).await
+}
+
+/// Read items from a queue, hiding them from other readers for the
+/// invisibility window. Read items stay in the queue until discarded.
+pub async fn read_queue_items(
+    params: &ReadQueueItemsParams,
+) -> Result<MemoryStoreQueueRead, Error> {
+    let client = reqwest::Client::new();
+    let url = queue_url(params.universe_id, &params.queue_id, Some("read"));
+    let mut query: QueryString = vec![];
+    if let Some(count) = params.count {
+        query.push(("count", count.to_string()));
+    }
+    if let Some(all_or_nothing) = params.all_or_nothing {
+        query.push(("allOrNothing", all_or_nothing.to_string()));
+    }
+    if let Some(invisibility_window) = params.invisibility_window {
+        query.push((
+            "invisibilityWindow",
+            format!("{}s", invisibility_window.as_secs()),
+        ));
+    }
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreQueueRead>(res).await
+}
+
+/// Remove the items of a read from a queue.
+pub async fn discard_queue_items(params: &DiscardQueueItemsParams) -> Result<(), Error> {
+    let body = serde_json::to_string(&json!({ "readId": params.read_id }))?;
+    let client = reqwest::Client::new();
+    let url = queue_url(params.universe_id, &params.queue_id, Some("discard"));
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    Ok(())
+}
+
+/// Start deleting every sorted map and queue of the universe. The flush runs
+/// in the background; poll the returned operation with
+/// `get_flush_operation` or `wait_for_flush`.
+pub async fn flush_memory_store(
+    params: &FlushMemoryStoreParams,
+) -> Result<MemoryStoreFlushOperation, Error> {
+    let client = reqwest::Client::new();
+    let universe_id = params.universe_id;
+    let url =
+        format!("https://apis.roblox.com/cloud/v2/universes/{universe_id}/memory-store:flush");
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body("{}")
+        .send()
+        .await?;
+    handle_res::<MemoryStoreFlushOperation>(res).await
+}
+
+/// Get the state of a flush operation.
+pub async fn get_flush_operation(
+    params: &GetFlushOperationParams,
+) -> Result<MemoryStoreFlushOperation, Error> {
+    let client = reqwest::Client::new();
+    let url = memory_store_url(params.universe_id, &["operations", &params.operation_id]);
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    handle_res::<MemoryStoreFlushOperation>(res).await
+}
+
+/// Poll a flush operation until it is done, or until `timeout` has passed.
+/// Returns the last state of the operation, which is not done on timeout.
+pub async fn wait_for_flush(
+    params: &GetFlushOperationParams,
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+) -> Result<MemoryStoreFlushOperation, Error> {
+    let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+    loop {
+        let operation = get_flush_operation(params).await?;
+        if operation.done {
+            return Ok(operation);
+        }
+        let next_poll = tokio::time::Instant::now() + poll_interval;
+        if deadline.is_some_and(|deadline| next_poll > deadline) {
+            return Ok(operation);
+        }
+        tokio::time::sleep_until(next_poll).await;
+    }
+}
@@ -0,0 +1,239 @@
+//! Consuming MemoryStore queues from outside the game.
+//!
+//! The consumer reads batches of items, hands every item to an async
+//! handler, and discards the batch once every item has been handled.
+//! MemoryStore discards a whole read at once, so a batch with a failed item
+//! is left in the queue, and all of its items are read again once the
+//! invisibility window ends. Items are read one at a time by default, so
+//! only the failed item comes back; larger batches save requests, but need
+//! idempotent handlers.
+//!
+//! Reads and discards that fail with a 429 or 5xx status, or without a
+//! response, are retried with exponential backoff. An error that remains
+//! stops the consumer and is recorded in the report along with everything
+//! handled until then.
+//!
+//! ```rust,no_run
+//! # async fn run() -> Result<(), rbxcloud::rbx::error::Error> {
+//! use std::time::Duration;
+//! use rbxcloud::rbx::{
+//!     types::UniverseId,
+//!     v2::{memory_store_consumer::QueueConsumer, Client},
+//! };
+//!
+//! let memory_store = Client::new("API_KEY").memory_store(UniverseId(9876543210));
+//! let consumer = QueueConsumer::new("Jobs").stop_when_empty(true);
+//! let report = memory_store
+//!     .consume_queue(&consumer, |item| async move {
+//!         println!("{}", item.data);
+//!         Ok::<(), String>(())
+//!     })
+//!     .await?;
+//! println!("{} items processed", report.processed);
+//! if let Some(err) = report.error {
+//!     eprintln!("stopped: {err}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{fmt::Display, future::Future, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rbx::error::Error;
+
+use super::{memory_store::MemoryStoreQueueItem, MemoryStoreClient};
+
+pub struct QueueConsumer {
+    pub queue_id: String,
+    /// Number of items read at once.
+    pub batch_size: u32,
+    /// How long read items are hidden from other readers. Should be longer
+    /// than handling a whole batch takes.
+    pub invisibility_window: Duration,
+    /// Wait between reads while the queue is empty.
+    pub poll_interval: Duration,
+    /// Stop at the first empty read instead of polling.
+    pub stop_when_empty: bool,
+    /// Stop after this many reads.
+    pub max_reads: Option<u64>,
+    /// Number of times a failed read or discard is retried.
+    pub retries: u32,
+    /// Wait before the first retry, doubled for every following one.
+    pub retry_delay: Duration,
+}
+
+impl QueueConsumer {
+    pub fn new(queue_id: &str) -> Self {
+        Self {
+            queue_id: queue_id.to_string(),
+            batch_size: 1,
+            invisibility_window: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(5),
+            stop_when_empty: false,
+            max_reads: None,
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn invisibility_window(mut self, invisibility_window: Duration) -> Self {
+        self.invisibility_window = invisibility_window;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn stop_when_empty(mut self, stop_when_empty: bool) -> Self {
+        self.stop_when_empty = stop_when_empty;
+        self
+    }
+
+    pub fn max_reads(mut self, max_reads: u64) -> Self {
+        self.max_reads = Some(max_reads);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueConsumerFailure {
+    pub read_id: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueConsumerReport {
+    /// Reads that returned items.
+    pub reads: u64,
+    /// Items handled successfully, including those of batches that were not
+    /// discarded.
+    pub processed: u64,
+    /// Items the handler failed on.
+    pub failed: u64,
+    /// Items discarded from the queue.
+    pub discarded: u64,
+    pub failures: Vec<QueueConsumerFailure>,
+    /// Read or discard error that stopped the consumer, once retries ran
+    /// out. Items of a batch that could not be discarded are read again.
+    pub error: Option<String>,
+}
+
+fn is_retryable(err: &Error) -> bool {
+    match err {
+        Error::HttpStatusError { code, .. } => *code == 429 || *code >= 500,
+        Error::ReqwestError(_) => true,
+        _ => false,
+    }
+}
+
+async fn with_retries<T, F, Fut>(consumer: &QueueConsumer, mut request: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut delay = consumer.retry_delay;
+    let mut retries = 0;
+    loop {
+        match request().await {
+            Err(err) if retries < consumer.retries && is_retryable(&err) => {
+                retries += 1;
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Read and handle queue items until the consumer's stop condition is met,
+/// or a read or discard fails after its retries.
+///
+/// Handler errors, and the error that stopped the consumer, are recorded in
+/// the report.
+pub async fn consume_queue<F, Fut, E>(
+    memory_store: &MemoryStoreClient,
+    consumer: &QueueConsumer,
+    mut handler: F,
+) -> Result<QueueConsumerReport, Error>
+where
+    F: FnMut(MemoryStoreQueueItem) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    let mut report = QueueConsumerReport::default();
+    let mut attempts = 0;
+    while consumer.max_reads.is_none_or(|max| attempts < max) {
+        attempts += 1;
+        let read = with_retries(consumer, || {
+            memory_store.read_queue_items(
+                &consumer.queue_id,
+                Some(consumer.batch_size),
+                None,
+                Some(consumer.invisibility_window),
+            )
+        })
+        .await;
+        let read = match read {
+            Ok(read) => read,
+            Err(err) => {
+                report.error = Some(err.to_string());
+                break;
+            }
+        };
+        if read.items.is_empty() {
+            if consumer.stop_when_empty {
+                break;
+            }
+            tokio::time::sleep(consumer.poll_interval).await;
+            continue;
+        }
+        report.reads += 1;
+        let count = read.items.len() as u64;
+        let mut failed = false;
+        for item in read.items {
+            match handler(item).await {
+                Ok(()) => report.processed += 1,
+                Err(err) => {
+                    failed = true;
+                    report.failed += 1;
+                    report.failures.push(QueueConsumerFailure {
+                        read_id: read.read_id.clone(),
+                        error: err.to_string(),
+                    });
+                }
+            }
+        }
+        if !failed {
+            let discarded = with_retries(consumer, || {
+                memory_store.discard_queue_items(&consumer.queue_id, &read.read_id)
+            })
+            .await;
+            if let Err(err) = discarded {
+                report.error = Some(err.to_string());
+                break;
+            }
+            report.discarded += count;
+        }
+    }
+    Ok(report)
+}
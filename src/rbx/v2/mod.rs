@@ -2,12 +2,32 @@
 //!
 //! Most usage should go through the `Client` struct.
 
+use datastore::{
+    CreateDataStoreEntryParams, DataStoreEntry, DataStoreEntryList, DataStoreEntryParams,
+    DataStoreEntryValue, DataStoreInfo, DataStoreList, DataStoreParams, GetDataStoreEntryParams,
+    IncrementDataStoreEntryParams, ListDataStoreEntriesParams, ListDataStoreEntryRevisionsParams,
+    ListDataStoresParams, SnapshotDataStoresParams, SnapshotDataStoresResponse,
+    UpdateDataStoreEntryParams,
+};
 use inventory::{InventoryItems, ListInventoryItemsParams};
 use luau_execution::{
     CreateLuauExecutionTaskParams, GetLuauExecutionSessionTaskLogsParams,
     GetLuauExecutionSessionTaskParams, LuauExecutionSessionTask, LuauExecutionSessionTaskLogPage,
     LuauExecutionTaskLogView, NewLuauExecutionSessionTask,
 };
+use memory_store::{
+    CreateSortedMapItemParams, DiscardQueueItemsParams, EnqueueItemParams, FlushMemoryStoreParams,
+    GetFlushOperationParams, ListSortedMapItemsParams, MemoryStoreFlushOperation,
+    MemoryStoreQueueItem, MemoryStoreQueueRead, MemoryStoreSortedMapItem,
+    MemoryStoreSortedMapItemList, ReadQueueItemsParams, SortedMapItemParams, SortedMapItemValue,
+    SortedMapOrder, UpdateSortedMapItemParams,
+};
+use memory_store_consumer::{QueueConsumer, QueueConsumerReport};
+use ordered_datastore::{
+    CreateOrderedDataStoreEntryParams, IncrementOrderedDataStoreEntryParams,
+    ListOrderedDataStoreEntriesParams, OrderedDataStoreEntry, OrderedDataStoreEntryList,
+    OrderedDataStoreEntryParams, UpdateOrderedDataStoreEntryParams,
+};
 use place::{GetPlaceParams, PlaceInfo, UpdatePlaceInfo, UpdatePlaceParams};
 use rand::{distr::Alphanumeric, Rng};
 use universe::{
@@ -32,20 +52,31 @@ use self::{
     notification::{Notification, NotificationParams, NotificationResponse},
     subscription::{GetSubscriptionParams, GetSubscriptionResponse, SubscriptionView},
 };
+pub mod datastore;
 pub mod group;
 pub(crate) mod http_err;
 pub mod inventory;
 pub mod luau_execution;
+pub mod memory_store;
+pub mod memory_store_consumer;
 pub mod notification;
+pub mod ordered_datastore;
 pub mod place;
 pub mod subscription;
 pub mod universe;
 pub mod user;
 pub mod user_restriction;
 
+use std::{fmt::Display, future::Future, time::Duration};
+
+use serde_json::Value;
+
 use crate::rbx::error::Error;
 
-use super::types::{GroupId, PlaceId, RobloxUserId, UniverseId};
+use super::{
+    types::{GroupId, PlaceId, RobloxUserId, UniverseId},
+    v1::datastore::EntryAttributes,
+};
 
 /// Access into the Roblox Open Cloud APIs.
 ///
@@ -60,6 +91,11 @@ pub struct Client {
     pub api_key: String,
 }
 
+pub struct DataStoreClient {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+}
+
 pub struct GroupClient {
     pub api_key: String,
     pub group_id: GroupId,
@@ -76,6 +112,11 @@ pub struct LuauExecutionClient {
     pub version_id: Option<String>,
 }
 
+pub struct MemoryStoreClient {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+}
+
 pub struct SubscriptionClient {
     pub api_key: String,
 }
@@ -85,6 +126,11 @@ pub struct NotificationClient {
     pub universe_id: UniverseId,
 }
 
+pub struct OrderedDataStoreClient {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+}
+
 pub struct PlaceClient {
     pub api_key: String,
     pub universe_id: UniverseId,
@@ -115,6 +161,190 @@ pub struct UserRestrictionParams {
     pub exclude_alt_accounts: Option<bool>,
 }
 
+impl DataStoreClient {
+    pub async fn list_data_stores(
+        &self,
+        max_page_size: Option<u32>,
+        filter: Option<String>,
+        page_token: Option<String>,
+        show_deleted: Option<bool>,
+    ) -> Result<DataStoreList, Error> {
+        datastore::list_data_stores(&ListDataStoresParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            max_page_size,
+            page_token,
+            filter,
+            show_deleted,
+        })
+        .await
+    }
+
+    pub async fn delete_data_store(&self, data_store_id: &str) -> Result<DataStoreInfo, Error> {
+        datastore::delete_data_store(&DataStoreParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn undelete_data_store(&self, data_store_id: &str) -> Result<DataStoreInfo, Error> {
+        datastore::undelete_data_store(&DataStoreParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn snapshot(&self) -> Result<SnapshotDataStoresResponse, Error> {
+        datastore::snapshot_data_stores(&SnapshotDataStoresParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+        })
+        .await
+    }
+
+    pub async fn list_entries(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        max_page_size: Option<u32>,
+        filter: Option<String>,
+        page_token: Option<String>,
+        show_deleted: Option<bool>,
+    ) -> Result<DataStoreEntryList, Error> {
+        datastore::list_data_store_entries(&ListDataStoreEntriesParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            max_page_size,
+            page_token,
+            filter,
+            show_deleted,
+        })
+        .await
+    }
+
+    pub async fn get_entry(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        revision_id: Option<String>,
+    ) -> Result<DataStoreEntry, Error> {
+        datastore::get_data_store_entry(&GetDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            revision_id,
+        })
+        .await
+    }
+
+    pub async fn create_entry(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        entry: DataStoreEntryValue,
+    ) -> Result<DataStoreEntry, Error> {
+        datastore::create_data_store_entry(&CreateDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            entry,
+        })
+        .await
+    }
+
+    pub async fn update_entry(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        entry: DataStoreEntryValue,
+        allow_missing: Option<bool>,
+    ) -> Result<DataStoreEntry, Error> {
+        datastore::update_data_store_entry(&UpdateDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            entry,
+            allow_missing,
+        })
+        .await
+    }
+
+    pub async fn delete_entry(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+    ) -> Result<(), Error> {
+        datastore::delete_data_store_entry(&DataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn increment_entry(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        amount: f64,
+        users: Vec<RobloxUserId>,
+        attributes: Option<EntryAttributes>,
+    ) -> Result<DataStoreEntry, Error> {
+        datastore::increment_data_store_entry(&IncrementDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            amount,
+            users,
+            attributes,
+        })
+        .await
+    }
+
+    pub async fn list_entry_revisions(
+        &self,
+        data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        max_page_size: Option<u32>,
+        filter: Option<String>,
+        page_token: Option<String>,
+    ) -> Result<DataStoreEntryList, Error> {
+        datastore::list_data_store_entry_revisions(&ListDataStoreEntryRevisionsParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            data_store_id: data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            max_page_size,
+            page_token,
+            filter,
+        })
+        .await
+    }
+}
+
 impl GroupClient {
     pub async fn get_info(&self) -> Result<GetGroupResponse, Error> {
         group::get_group(&GetGroupParams {
@@ -238,6 +468,194 @@ impl LuauExecutionClient {
     }
 }
 
+impl MemoryStoreClient {
+    pub async fn list_sorted_map_items(
+        &self,
+        sorted_map_id: &str,
+        max_page_size: Option<u32>,
+        order_by: Option<SortedMapOrder>,
+        filter: Option<String>,
+        page_token: Option<String>,
+    ) -> Result<MemoryStoreSortedMapItemList, Error> {
+        memory_store::list_sorted_map_items(&ListSortedMapItemsParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            sorted_map_id: sorted_map_id.to_string(),
+            max_page_size,
+            page_token,
+            order_by,
+            filter,
+        })
+        .await
+    }
+
+    pub async fn get_sorted_map_item(
+        &self,
+        sorted_map_id: &str,
+        item_id: &str,
+    ) -> Result<MemoryStoreSortedMapItem, Error> {
+        memory_store::get_sorted_map_item(&SortedMapItemParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            sorted_map_id: sorted_map_id.to_string(),
+            item_id: item_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn create_sorted_map_item(
+        &self,
+        sorted_map_id: &str,
+        item_id: &str,
+        item: SortedMapItemValue,
+    ) -> Result<MemoryStoreSortedMapItem, Error> {
+        memory_store::create_sorted_map_item(&CreateSortedMapItemParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            sorted_map_id: sorted_map_id.to_string(),
+            item_id: item_id.to_string(),
+            item,
+        })
+        .await
+    }
+
+    pub async fn update_sorted_map_item(
+        &self,
+        sorted_map_id: &str,
+        item_id: &str,
+        item: SortedMapItemValue,
+        allow_missing: Option<bool>,
+    ) -> Result<MemoryStoreSortedMapItem, Error> {
+        memory_store::update_sorted_map_item(&UpdateSortedMapItemParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            sorted_map_id: sorted_map_id.to_string(),
+            item_id: item_id.to_string(),
+            item,
+            allow_missing,
+        })
+        .await
+    }
+
+    pub async fn delete_sorted_map_item(
+        &self,
+        sorted_map_id: &str,
+        item_id: &str,
+    ) -> Result<(), Error> {
+        memory_store::delete_sorted_map_item(&SortedMapItemParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            sorted_map_id: sorted_map_id.to_string(),
+            item_id: item_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn enqueue_item(
+        &self,
+        queue_id: &str,
+        data: Value,
+        priority: f64,
+        ttl: Duration,
+    ) -> Result<MemoryStoreQueueItem, Error> {
+        memory_store::enqueue_item(&EnqueueItemParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            queue_id: queue_id.to_string(),
+            data,
+            priority,
+            ttl,
+        })
+        .await
+    }
+
+    pub async fn read_queue_items(
+        &self,
+        queue_id: &str,
+        count: Option<u32>,
+        all_or_nothing: Option<bool>,
+        invisibility_window: Option<Duration>,
+    ) -> Result<MemoryStoreQueueRead, Error> {
+        memory_store::read_queue_items(&ReadQueueItemsParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            queue_id: queue_id.to_string(),
+            count,
+            all_or_nothing,
+            invisibility_window,
+        })
+        .await
+    }
+
+    pub async fn discard_queue_items(&self, queue_id: &str, read_id: &str) -> Result<(), Error> {
+        memory_store::discard_queue_items(&DiscardQueueItemsParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            queue_id: queue_id.to_string(),
+            read_id: read_id.to_string(),
+        })
+        .await
+    }
+
+    /// Read queue items in a loop, handing each to `handler` and discarding
+    /// every batch that was handled without errors. A read or discard that
+    /// still fails after retries stops the loop and is recorded in the
+    /// report.
+    pub async fn consume_queue<F, Fut, E>(
+        &self,
+        consumer: &QueueConsumer,
+        handler: F,
+    ) -> Result<QueueConsumerReport, Error>
+    where
+        F: FnMut(MemoryStoreQueueItem) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Display,
+    {
+        memory_store_consumer::consume_queue(self, consumer, handler).await
+    }
+
+    /// Start deleting every sorted map and queue of the universe.
+    pub async fn flush(&self) -> Result<MemoryStoreFlushOperation, Error> {
+        memory_store::flush_memory_store(&FlushMemoryStoreParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+        })
+        .await
+    }
+
+    pub async fn get_flush_operation(
+        &self,
+        operation_id: &str,
+    ) -> Result<MemoryStoreFlushOperation, Error> {
+        memory_store::get_flush_operation(&GetFlushOperationParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            operation_id: operation_id.to_string(),
+        })
+        .await
+    }
+
+    /// Poll a flush operation until it is done, or until `timeout` has
+    /// passed.
+    pub async fn wait_for_flush(
+        &self,
+        operation_id: &str,
+        poll_interval: Duration,
+        timeout: Option<Duration>,
+    ) -> Result<MemoryStoreFlushOperation, Error> {
+        memory_store::wait_for_flush(
+            &GetFlushOperationParams {
+                api_key: self.api_key.clone(),
+                universe_id: self.universe_id,
+                operation_id: operation_id.to_string(),
+            },
+            poll_interval,
+            timeout,
+        )
+        .await
+    }
+}
+
 impl SubscriptionClient {
     pub async fn get(
         &self,
@@ -272,6 +690,120 @@ impl NotificationClient {
     }
 }
 
+impl OrderedDataStoreClient {
+    pub async fn list_entries(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        max_page_size: Option<u32>,
+        order_by: Option<String>,
+        filter: Option<String>,
+        page_token: Option<String>,
+    ) -> Result<OrderedDataStoreEntryList, Error> {
+        ordered_datastore::list_ordered_data_store_entries(&ListOrderedDataStoreEntriesParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            ordered_data_store_id: ordered_data_store_id.to_string(),
+            scope,
+            max_page_size,
+            page_token,
+            order_by,
+            filter,
+        })
+        .await
+    }
+
+    pub async fn create_entry(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        value: i64,
+    ) -> Result<OrderedDataStoreEntry, Error> {
+        ordered_datastore::create_ordered_data_store_entry(&CreateOrderedDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            ordered_data_store_id: ordered_data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            value,
+        })
+        .await
+    }
+
+    pub async fn get_entry(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+    ) -> Result<OrderedDataStoreEntry, Error> {
+        ordered_datastore::get_ordered_data_store_entry(&OrderedDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            ordered_data_store_id: ordered_data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn update_entry(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        value: i64,
+        allow_missing: Option<bool>,
+    ) -> Result<OrderedDataStoreEntry, Error> {
+        ordered_datastore::update_ordered_data_store_entry(&UpdateOrderedDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            ordered_data_store_id: ordered_data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+            value,
+            allow_missing,
+        })
+        .await
+    }
+
+    pub async fn delete_entry(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+    ) -> Result<(), Error> {
+        ordered_datastore::delete_ordered_data_store_entry(&OrderedDataStoreEntryParams {
+            api_key: self.api_key.clone(),
+            universe_id: self.universe_id,
+            ordered_data_store_id: ordered_data_store_id.to_string(),
+            scope,
+            entry_id: entry_id.to_string(),
+        })
+        .await
+    }
+
+    pub async fn increment_entry(
+        &self,
+        ordered_data_store_id: &str,
+        scope: Option<String>,
+        entry_id: &str,
+        amount: i64,
+    ) -> Result<OrderedDataStoreEntry, Error> {
+        ordered_datastore::increment_ordered_data_store_entry(
+            &IncrementOrderedDataStoreEntryParams {
+                api_key: self.api_key.clone(),
+                universe_id: self.universe_id,
+                ordered_data_store_id: ordered_data_store_id.to_string(),
+                scope,
+                entry_id: entry_id.to_string(),
+                amount,
+            },
+        )
+        .await
+    }
+}
+
 impl PlaceClient {
     pub async fn get(&self) -> Result<PlaceInfo, Error> {
         place::get_place(&GetPlaceParams {
@@ -406,7 +938,7 @@ impl UserRestrictionClient {
             user_id: params.user_id,
             idempotency_key: Some(idempotency_key),
             active: params.active,
-            duration: params.duration.and_then(|d| Some(format!("{}s", d))),
+            duration: params.duration.map(|d| format!("{}s", d)),
             private_reason: params.private_reason.clone(),
             display_reason: params.display_reason.clone(),
             exclude_alt_accounts: params.exclude_alt_accounts,
@@ -440,6 +972,13 @@ impl Client {
         }
     }
 
+    pub fn datastore(&self, universe_id: UniverseId) -> DataStoreClient {
+        DataStoreClient {
+            api_key: self.api_key.clone(),
+            universe_id,
+        }
+    }
+
     pub fn group(&self, group_id: GroupId) -> GroupClient {
         GroupClient {
             api_key: self.api_key.clone(),
@@ -467,6 +1006,13 @@ impl Client {
         }
     }
 
+    pub fn memory_store(&self, universe_id: UniverseId) -> MemoryStoreClient {
+        MemoryStoreClient {
+            api_key: self.api_key.clone(),
+            universe_id,
+        }
+    }
+
     pub fn subscription(&self) -> SubscriptionClient {
         SubscriptionClient {
             api_key: self.api_key.clone(),
@@ -480,6 +1026,13 @@ impl Client {
         }
     }
 
+    pub fn ordered_datastore(&self, universe_id: UniverseId) -> OrderedDataStoreClient {
+        OrderedDataStoreClient {
+            api_key: self.api_key.clone(),
+            universe_id,
+        }
+    }
+
     pub fn place(&self, universe_id: UniverseId, place_id: PlaceId) -> PlaceClient {
         PlaceClient {
             api_key: self.api_key.clone(),
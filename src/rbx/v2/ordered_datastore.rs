@@ -0,0 +1,261 @@
+//! Cloud v2 OrderedDataStore API operations.
+//!
+//! Typically, these operations should be consumed through the
+//! `OrderedDataStoreClient` struct, obtained through
+//! `Client::ordered_datastore()`.
+use reqwest::{Response, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+
+use crate::rbx::{error::Error, types::UniverseId, util::QueryString};
+
+use super::http_err::handle_http_err_response;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedDataStoreEntry {
+    pub path: String,
+    pub id: String,
+    pub value: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedDataStoreEntryList {
+    #[serde(default)]
+    pub ordered_data_store_entries: Vec<OrderedDataStoreEntry>,
+    pub next_page_token: Option<String>,
+}
+
+pub struct ListOrderedDataStoreEntriesParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub ordered_data_store_id: String,
+    pub scope: Option<String>,
+    pub max_page_size: Option<u32>,
+    pub page_token: Option<String>,
+    /// `value` (ascending, the default) or `value desc`.
+    pub order_by: Option<String>,
+    /// Range of values, e.g. `entry >= 10 && entry <= 50`.
+    pub filter: Option<String>,
+}
+
+pub struct OrderedDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub ordered_data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+}
+
+pub struct CreateOrderedDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub ordered_data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub value: i64,
+}
+
+pub struct UpdateOrderedDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub ordered_data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub value: i64,
+    /// Create the entry if it does not exist.
+    pub allow_missing: Option<bool>,
+}
+
+pub struct IncrementOrderedDataStoreEntryParams {
+    pub api_key: String,
+    pub universe_id: UniverseId,
+    pub ordered_data_store_id: String,
+    pub scope: Option<String>,
+    pub entry_id: String,
+    pub amount: i64,
+}
+
+/// URL of the entries collection of an OrderedDataStore scope, or of the
+/// entry (plus an optional custom method, e.g. `:increment`) when `entry` is
+/// given. The scope defaults to `global`.
+fn entry_url(
+    universe_id: UniverseId,
+    ordered_data_store_id: &str,
+    scope: &Option<String>,
+    entry: Option<&str>,
+) -> Url {
+    let mut url = Url::parse(&format!(
+        "https://apis.roblox.com/cloud/v2/universes/{universe_id}"
+    ))
+    .expect("valid base url");
+    {
+        let mut segments = url.path_segments_mut().expect("base url has a path");
+        segments.extend([
+            "ordered-data-stores",
+            ordered_data_store_id,
+            "scopes",
+            scope.as_deref().unwrap_or("global"),
+            "entries",
+        ]);
+        if let Some(entry) = entry {
+            segments.push(entry);
+        }
+    }
+    url
+}
+
+async fn handle_res<T: DeserializeOwned>(res: Response) -> Result<T, Error> {
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    let body = res.json::<T>().await?;
+    Ok(body)
+}
+
+/// List the entries of an OrderedDataStore scope.
+pub async fn list_ordered_data_store_entries(
+    params: &ListOrderedDataStoreEntriesParams,
+) -> Result<OrderedDataStoreEntryList, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        None,
+    );
+    let mut query: QueryString = vec![];
+    if let Some(max_page_size) = params.max_page_size {
+        query.push(("maxPageSize", max_page_size.to_string()));
+    }
+    if let Some(page_token) = &params.page_token {
+        query.push(("pageToken", page_token.to_string()));
+    }
+    if let Some(order_by) = &params.order_by {
+        query.push(("orderBy", order_by.to_string()));
+    }
+    if let Some(filter) = &params.filter {
+        query.push(("filter", filter.to_string()));
+    }
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .query(&query)
+        .send()
+        .await?;
+    handle_res::<OrderedDataStoreEntryList>(res).await
+}
+
+/// Create an entry. Fails if the entry already exists.
+pub async fn create_ordered_data_store_entry(
+    params: &CreateOrderedDataStoreEntryParams,
+) -> Result<OrderedDataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        None,
+    );
+    let body = serde_json::to_string(&json!({ "value": params.value }))?;
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&[("id", &params.entry_id)])
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<OrderedDataStoreEntry>(res).await
+}
+
+/// Get an entry.
+pub async fn get_ordered_data_store_entry(
+    params: &OrderedDataStoreEntryParams,
+) -> Result<OrderedDataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        Some(&params.entry_id),
+    );
+    let res = client
+        .get(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    handle_res::<OrderedDataStoreEntry>(res).await
+}
+
+/// Update the value of an entry.
+pub async fn update_ordered_data_store_entry(
+    params: &UpdateOrderedDataStoreEntryParams,
+) -> Result<OrderedDataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        Some(&params.entry_id),
+    );
+    let mut query: QueryString = vec![];
+    if let Some(allow_missing) = params.allow_missing {
+        query.push(("allowMissing", allow_missing.to_string()));
+    }
+    let body = serde_json::to_string(&json!({ "value": params.value }))?;
+    let res = client
+        .patch(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .query(&query)
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<OrderedDataStoreEntry>(res).await
+}
+
+/// Delete an entry.
+pub async fn delete_ordered_data_store_entry(
+    params: &OrderedDataStoreEntryParams,
+) -> Result<(), Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        Some(&params.entry_id),
+    );
+    let res = client
+        .delete(url)
+        .header("x-api-key", &params.api_key)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        return handle_http_err_response(res).await;
+    }
+    Ok(())
+}
+
+/// Add to the value of an entry, creating it if it does not exist.
+pub async fn increment_ordered_data_store_entry(
+    params: &IncrementOrderedDataStoreEntryParams,
+) -> Result<OrderedDataStoreEntry, Error> {
+    let client = reqwest::Client::new();
+    let url = entry_url(
+        params.universe_id,
+        &params.ordered_data_store_id,
+        &params.scope,
+        Some(&format!("{}:increment", params.entry_id)),
+    );
+    let body = serde_json::to_string(&json!({ "amount": params.amount }))?;
+    let res = client
+        .post(url)
+        .header("x-api-key", &params.api_key)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await?;
+    handle_res::<OrderedDataStoreEntry>(res).await
+}